@@ -0,0 +1,43 @@
+//! Generates the parser of the language from test.grammar: the LL(1)
+//! production table, the AST productions and the lexer, written to
+//! `$OUT_DIR/grammar.rs` and included by `compiler::embedded`.
+
+#[path = "src"]
+mod src {
+    // the parser reads the tables through `get_production_table_entry`,
+    // `get_production_table_tokens_for_nt` and `get_ast_prod`, unused here
+    #[allow(dead_code, unused_imports)]
+    pub mod grammar;
+    pub mod grammar_file;
+}
+use src::{grammar, grammar_file};
+
+use std::{io::Write, path::Path};
+
+use grammar::Grammar;
+use grammar_file::GrammarFile;
+
+const GRAMMAR: &str = "test.grammar";
+
+fn main() {
+    println!("cargo:rerun-if-changed={GRAMMAR}");
+    println!("cargo:rerun-if-changed=src/grammar.rs");
+    println!("cargo:rerun-if-changed=src/grammar");
+    println!("cargo:rerun-if-changed=src/grammar_file.rs");
+    println!("cargo:rerun-if-changed=src/grammar_file");
+
+    let mut gf = GrammarFile::from_file(GRAMMAR).expect("could not read the grammar");
+    let rulestrings = grammar_file::parse_rules(&mut gf);
+    let mut grammar = Grammar::from_rule_string(rulestrings);
+    grammar.calculate_first_sets();
+    grammar.calculate_follow_sets();
+    if !grammar::is_ll1_grammar(&grammar) {
+        panic!("{GRAMMAR} is not LL1");
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("grammar.rs");
+    let mut buf = std::io::BufWriter::new(std::fs::File::create(out).unwrap());
+    gf.gen_rust(&mut buf).unwrap();
+    grammar.gen_rust(&mut buf).unwrap();
+    buf.flush().unwrap();
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use crate::error::{report_error_ext, Error, ErrorCode};
+
+use super::*;
+
+/// Top-level `const int/float/string NAME = <expr>;`, replaced by its value
+/// wherever `NAME` is used, like the builtin identifiers.
+#[derive(Debug, Clone)]
+pub struct Const {
+    pub name: Located<String>,
+    pub typ: ExprType,
+    pub value: Expr,
+}
+
+fn type_name(t: ExprType) -> &'static str {
+    match t {
+        ExprType::Int => "int",
+        ExprType::Float => "float",
+        ExprType::String => "string",
+        ExprType::Vararg => "vararg",
+    }
+}
+
+/// Why folding left the int operation `e` on literals: it overflows or it
+/// divides by zero.
+fn unfolded(e: &Expr) -> Option<&'static str> {
+    let int = |e: &Expr| matches!(e, Expr::Int(_));
+    match e {
+        Expr::Div(a, b, _) | Expr::Modulo(a, b, _) if int(a) => match b.as_ref() {
+            Expr::Int(j) if *j.val() == 0 => Some("division by zero"),
+            Expr::Int(_) => Some("int overflow"),
+            _ => None,
+        },
+        Expr::Add(a, b, _) | Expr::Sub(a, b, _) | Expr::Mul(a, b, _) if int(a) && int(b) => {
+            Some("int overflow")
+        }
+        Expr::Uminus(a, _) if int(a) => Some("int overflow"),
+        _ => None,
+    }
+}
+
+impl Const {
+    /// Folds the value, with the identifiers of `idents` substituted,
+    /// down to a literal of the declared type.
+    pub fn evaluate(&self, idents: &HashMap<String, Expr>) -> Result<Expr, Error> {
+        let mut value = self.value.clone();
+        value.replace_all_id(idents);
+        value.anotate()?;
+        value.constant_fold();
+        let mut failed = None;
+        value.visit_mut(&mut |e| {
+            if failed.is_none() {
+                failed = unfolded(e).map(|why| (e.loc(), why));
+            }
+        });
+        if let Some((loc, why)) = failed {
+            report_error_ext(
+                ErrorCode::NotConstant,
+                &loc,
+                &format!("value of `{}` can't be computed: {why}", self.name.val()),
+                why,
+            );
+            return Err(Error::Simple("expression is not constant".to_owned()));
+        }
+        if !matches!(value, Expr::Int(_) | Expr::Float(_) | Expr::Str(_)) {
+            report_error_ext(
+                ErrorCode::NotConstant,
+                &value.loc(),
+                &format!("value of `{}` is not a constant", self.name.val()),
+                "not constant",
+            );
+            return Err(Error::Simple("expression is not constant".to_owned()));
+        }
+        let found = value.get_type()?;
+        if found != self.typ {
+            report_error_ext(
+                ErrorCode::ConstantType,
+                &value.loc(),
+                &format!(
+                    "`{}` is declared {} but its value is {}",
+                    self.name.val(),
+                    type_name(self.typ),
+                    type_name(found)
+                ),
+                &format!("expected {}", type_name(self.typ)),
+            );
+            return Err(Error::Simple("mismatched constant type".to_owned()));
+        }
+        Ok(value)
+    }
+
+    /// A value of the declared type, for a constant whose value is wrong.
+    pub fn placeholder(&self) -> Expr {
+        let loc = self.name.loc().clone();
+        match self.typ {
+            ExprType::Float => Expr::Float(Located::new(0.0, loc)),
+            ExprType::String => Expr::Str(Located::new(String::new(), loc)),
+            _ => Expr::Int(Located::new(0, loc)),
+        }
+    }
+}
+
+fn resolve_const(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
+    if typ.len() != 1 {
+        return Err(Error::Grammar(
+            "Const command is composed of 1 sub command".to_owned(),
+        ));
+    }
+    if args.len() != 2 {
+        return Err(Error::Grammar(
+            "Const command takes 2 parameters".to_owned(),
+        ));
+    }
+    let typ = match &typ[0][..] {
+        "Int" => ExprType::Int,
+        "Float" => ExprType::Float,
+        "String" => ExprType::String,
+        t => return Err(Error::Grammar(format!("Unknown Const subcommand {t}"))),
+    };
+    Ok(AstNode::Constant(Const {
+        name: args[0].clone().token().id_loc(),
+        typ,
+        value: args[1].clone().expr(),
+    }))
+}
+
+/// `enum Name { A = 0, B, ... }`, kept as written for the formatter.
+#[derive(Debug, Clone)]
+pub struct Enum {
+    pub name: Located<String>,
+    /// The variants, with their value if it is written.
+    pub variants: Vec<(Located<String>, Option<Expr>)>,
+}
+
+impl Enum {
+    /// The prefix up to a `_` which all the variants share, like `AIM_` in
+    /// `AimMode`, if there are several of them.
+    fn prefix(&self) -> &str {
+        let Some(((first, _), rest)) = self.variants.split_first() else {
+            return "";
+        };
+        if rest.is_empty() {
+            return "";
+        }
+        let first = first.val();
+        let common = rest.iter().fold(first.len(), |len, (v, _)| {
+            first
+                .bytes()
+                .zip(v.val().bytes())
+                .take(len)
+                .take_while(|(a, b)| a == b)
+                .count()
+        });
+        first[..common].rfind('_').map_or("", |p| &first[..=p])
+    }
+
+    /// The int constants `Name::A`, `Name::B`... and their bare aliases `A`,
+    /// `B`... A variant without value is the previous one plus 1, the first
+    /// one defaults to 0.
+    /// The variants sharing a prefix are also named without it in the enum:
+    /// `AimMode::AT` is `AimMode::AIM_AT`.
+    pub fn consts(&self) -> Vec<Const> {
+        let prefix = self.prefix();
+        let names: Vec<&str> = self
+            .variants
+            .iter()
+            .map(|(v, _)| v.val().as_str())
+            .collect();
+        let mut consts = Vec::new();
+        let mut prev: Option<String> = None;
+        for (variant, value) in &self.variants {
+            let loc = variant.loc().clone();
+            let value = match (value, prev) {
+                (Some(e), _) => e.clone(),
+                (None, None) => Expr::Int(Located::new(0, loc.clone())),
+                // at the variant, where an overflow is reported
+                (None, Some(p)) => Expr::Add(
+                    Box::new(Expr::Id(Located::new(p, loc.clone()))),
+                    Box::new(Expr::Int(Located::new(1, loc.clone()))),
+                    None,
+                ),
+            };
+            let qualified = format!("{}::{}", self.name.val(), variant.val());
+            let id = Expr::Id(Located::new(qualified.clone(), loc.clone()));
+            consts.push(Const {
+                name: Located::new(qualified.clone(), loc.clone()),
+                typ: ExprType::Int,
+                value,
+            });
+            consts.push(Const {
+                name: variant.clone(),
+                typ: ExprType::Int,
+                value: id.clone(),
+            });
+            let short = variant
+                .val()
+                .strip_prefix(prefix)
+                .filter(|s| !prefix.is_empty() && printer::is_ident(s) && !names.contains(s));
+            if let Some(short) = short {
+                consts.push(Const {
+                    name: Located::new(format!("{}::{short}", self.name.val()), loc),
+                    typ: ExprType::Int,
+                    value: id,
+                });
+            }
+            prev = Some(qualified);
+        }
+        consts
+    }
+}
+
+fn resolve_enum(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
+    match typ {
+        [] => {}
+        [v] if v == "Variant" => {
+            if args.len() != 2 {
+                return Err(Error::Grammar(
+                    "Enum::Variant command takes 2 parameters".to_owned(),
+                ));
+            }
+            return Ok(AstNode::Data {
+                dtype: "Enum::Variant".to_owned(),
+                children: args.to_vec(),
+            });
+        }
+        _ => return Err(Error::Grammar(format!("Unknown Enum subcommand {typ:?}"))),
+    }
+    if args.len() != 2 {
+        return Err(Error::Grammar("Enum command takes 2 parameters".to_owned()));
+    }
+    let variants = args[1]
+        .clone()
+        .list()
+        .into_iter()
+        .map(|v| {
+            let (_, children) = v.data();
+            let value = match &children[1] {
+                AstNode::Expr(e) => Some(e.clone()),
+                _ => None,
+            };
+            (children[0].clone().token().id_loc(), value)
+        })
+        .collect();
+    Ok(AstNode::EnumDecl(Enum {
+        name: args[0].clone().token().id_loc(),
+        variants,
+    }))
+}
+
+pub fn fill_executor(resolver: &mut AstResolver<AstNode>) {
+    resolver.add_func("Const", resolve_const);
+    resolver.add_func("Enum", resolve_enum);
+}
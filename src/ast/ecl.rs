@@ -1,21 +1,135 @@
-use crate::error::Error;
+use std::collections::HashMap;
+
+use crate::{
+    ecl_instructions::InstructionSet,
+    error::{emit, report_error_ext, Diagnostic, Error, ErrorCode, Errors},
+    scpt::Encoding,
+};
 
 use super::*;
 
+/// `include "file.ecs";`, replaced by the content of the file before the
+/// script is processed.
+#[derive(Debug, Clone)]
+pub struct Include {
+    /// As written, relative to the including file.
+    pub path: Located<String>,
+    /// Number of constants declared before the include, where the constants
+    /// of the file go.
+    pub consts_before: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Ecl {
-    pub ecli: Vec<String>,
-    pub anmi: Vec<String>,
+    pub ecli: Vec<Located<String>>,
+    pub anmi: Vec<Located<String>>,
+    pub includes: Vec<Include>,
+    pub consts: Vec<Const>,
+    /// The enums as written, their constants being in `consts`.
+    pub enums: Vec<Enum>,
     pub subs: Vec<Sub>,
 }
 
 impl Ecl {
-    pub fn process(&mut self) -> Result<(), Error> {
+    /// Replaces the includes by the content of their files: `files[i]` is the
+    /// already expanded file of `self.includes[i]`, or `None` if it was included
+    /// before. The subs of the files go before the script's own.
+    pub fn expand_includes(&mut self, files: Vec<Option<Ecl>>) {
+        let includes = std::mem::take(&mut self.includes);
+        let mut shift = 0;
+        let mut subs = Vec::new();
+        for (inc, file) in includes.iter().zip(files) {
+            let Some(file) = file else { continue };
+            for name in file.ecli {
+                if !self.ecli.iter().any(|n| n.val() == name.val()) {
+                    self.ecli.push(name);
+                }
+            }
+            for name in file.anmi {
+                if !self.anmi.iter().any(|n| n.val() == name.val()) {
+                    self.anmi.push(name);
+                }
+            }
+            let at = inc.consts_before + shift;
+            shift += file.consts.len();
+            self.consts.splice(at..at, file.consts);
+            self.enums.extend(file.enums);
+            subs.extend(file.subs);
+        }
+        subs.append(&mut self.subs);
+        self.subs = subs;
+    }
+
+    /// `prelude` holds the constants declared before the script.
+    /// Every sub is processed, even after an error in another one.
+    /// The subs are only known to be unused in a script without `ecli`,
+    /// the subs of the other files being able to call them.
+    pub fn process(
+        &mut self,
+        instructions: &InstructionSet,
+        encoding: Encoding,
+        prelude: &[Const],
+    ) -> Result<(), Error> {
+        let mut errors = Errors::default();
+        for name in self.ecli.iter().chain(&self.anmi) {
+            errors.check(|| name.encode(encoding));
+        }
+        let idents = self.constants(prelude, &mut errors);
+        self.check_sub_names(&mut errors);
+        let subs: Vec<_> = self.subs.iter().map(|s| s.name.val().clone()).collect();
         for s in &mut self.subs {
-            s.process()?;
+            errors.check(|| s.process(instructions, encoding, &idents, &subs));
+        }
+        errors.finish(())?;
+        if self.ecli.is_empty() {
+            lints::unused_subs(self);
         }
         Ok(())
     }
+
+    /// Reports the subs named like an earlier one, both being possibly in
+    /// included files.
+    fn check_sub_names(&self, errors: &mut Errors) {
+        let mut seen: HashMap<&str, &Located<String>> = HashMap::new();
+        for s in &self.subs {
+            let Some(first) = seen.get(s.name.val().as_str()) else {
+                seen.insert(s.name.val(), &s.name);
+                continue;
+            };
+            let d = Diagnostic::error(
+                ErrorCode::AlreadyDefined,
+                s.name.loc(),
+                &format!("sub `{}` is already defined", s.name.val()),
+                "redefined here",
+            );
+            emit(d.with_note(Diagnostic::note(first.loc(), "first defined here")));
+            errors.fail();
+        }
+    }
+
+    /// The builtin identifiers and the values of the constants, each constant
+    /// being able to use the ones declared before it. A constant whose value
+    /// is wrong still gets one of its type, to not report its uses too.
+    fn constants(&self, prelude: &[Const], errors: &mut Errors) -> HashMap<String, Expr> {
+        let mut idents = builtin_idents::builtins();
+        for c in prelude.iter().chain(&self.consts) {
+            if idents.contains_key(c.name.val()) {
+                report_error_ext(
+                    ErrorCode::AlreadyDefined,
+                    c.name.loc(),
+                    &format!("`{}` is already defined", c.name.val()),
+                    "redefined here",
+                );
+                errors.fail();
+                continue;
+            }
+            let value = errors
+                .check(|| c.evaluate(&idents))
+                .unwrap_or_else(|| c.placeholder());
+            idents.insert(c.name.val().clone(), value);
+        }
+        idents
+    }
 }
 
 fn resolve_ecl(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
@@ -29,23 +143,58 @@ fn resolve_ecl(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
         .clone()
         .list()
         .into_iter()
-        .map(|n| n.token().strn())
+        .map(|n| n.token().strn_loc())
         .collect();
     let anmi = args[1]
         .clone()
         .list()
         .into_iter()
-        .map(|n| n.token().strn())
+        .map(|n| n.token().strn_loc())
         .collect();
-    let subs = args[2]
-        .clone()
-        .list()
-        .into_iter()
-        .map(|n| n.sub())
-        .collect();
-    Ok(AstNode::Ecl(Ecl { ecli, anmi, subs }))
+    let mut consts = Vec::new();
+    let mut includes = Vec::new();
+    let mut enums = Vec::new();
+    let mut subs = Vec::new();
+    for n in args[2].clone().list() {
+        match n {
+            AstNode::Constant(c) => consts.push(c),
+            AstNode::EnumDecl(e) => {
+                consts.extend(e.consts());
+                enums.push(e);
+            }
+            AstNode::Data { dtype, children } if dtype == "Include" => includes.push(Include {
+                path: children[0].clone().token().strn_loc(),
+                consts_before: consts.len(),
+            }),
+            n => subs.push(n.sub()),
+        }
+    }
+    Ok(AstNode::Ecl(Ecl {
+        ecli,
+        anmi,
+        includes,
+        consts,
+        enums,
+        subs,
+    }))
+}
+
+fn resolve_include(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
+    if !typ.is_empty() {
+        return Err(Error::Grammar(
+            "Include command has no subcommands".to_owned(),
+        ));
+    }
+    if args.len() != 1 {
+        return Err(Error::Grammar("Include command takes 1 param".to_owned()));
+    }
+    Ok(AstNode::Data {
+        dtype: "Include".to_owned(),
+        children: args.to_vec(),
+    })
 }
 
 pub fn fill_executor(resolver: &mut AstResolver<AstNode>) {
     resolver.add_func("Ecl", resolve_ecl);
+    resolver.add_func("Include", resolve_include);
 }
@@ -1,5 +1,5 @@
 use crate::{
-    error::{report_error, report_error_ext, Error},
+    error::{report_error, report_error_ext, Error, ErrorCode},
     lexer::Location,
 };
 
@@ -53,6 +53,8 @@ pub enum Expr {
 }
 
 impl Expr {
+    /// Computes the operations on literals. An int operation which overflows
+    /// or divides by zero is left as is, for `Const::evaluate` to report.
     pub fn constant_fold(&mut self) {
         match self {
             Self::Int(_) => {}
@@ -66,7 +68,9 @@ impl Expr {
                 r.constant_fold();
                 match (l.as_ref(), r.as_ref()) {
                     (Expr::Int(i), Expr::Int(j)) => {
-                        *self = Expr::Int(Located::new(i.val() + j.val(), i.loc().merge(j.loc())));
+                        if let Some(k) = i.val().checked_add(*j.val()) {
+                            *self = Expr::Int(Located::new(k, i.loc().merge(j.loc())));
+                        }
                     }
                     (Expr::Float(i), Expr::Float(j)) => {
                         *self = Expr::Float(Located::new(i.val() + j.val(), i.loc().merge(j.loc())))
@@ -86,7 +90,9 @@ impl Expr {
                 r.constant_fold();
                 match (l.as_ref(), r.as_ref()) {
                     (Expr::Int(i), Expr::Int(j)) => {
-                        *self = Expr::Int(Located::new(i.val() - j.val(), i.loc().merge(j.loc())))
+                        if let Some(k) = i.val().checked_sub(*j.val()) {
+                            *self = Expr::Int(Located::new(k, i.loc().merge(j.loc())));
+                        }
                     }
                     (Expr::Float(i), Expr::Float(j)) => {
                         *self = Expr::Float(Located::new(i.val() - j.val(), i.loc().merge(j.loc())))
@@ -99,7 +105,9 @@ impl Expr {
                 r.constant_fold();
                 match (l.as_ref(), r.as_ref()) {
                     (Expr::Int(i), Expr::Int(j)) => {
-                        *self = Expr::Int(Located::new(i.val() * j.val(), i.loc().merge(j.loc())))
+                        if let Some(k) = i.val().checked_mul(*j.val()) {
+                            *self = Expr::Int(Located::new(k, i.loc().merge(j.loc())));
+                        }
                     }
                     (Expr::Float(i), Expr::Float(j)) => {
                         *self = Expr::Float(Located::new(i.val() * j.val(), i.loc().merge(j.loc())))
@@ -119,7 +127,9 @@ impl Expr {
                 r.constant_fold();
                 match (l.as_ref(), r.as_ref()) {
                     (Expr::Int(i), Expr::Int(j)) => {
-                        *self = Expr::Int(Located::new(i.val() / j.val(), i.loc().merge(j.loc())))
+                        if let Some(k) = i.val().checked_div(*j.val()) {
+                            *self = Expr::Int(Located::new(k, i.loc().merge(j.loc())));
+                        }
                     }
                     (Expr::Float(i), Expr::Float(j)) => {
                         *self = Expr::Float(Located::new(i.val() / j.val(), i.loc().merge(j.loc())))
@@ -131,13 +141,19 @@ impl Expr {
                 l.constant_fold();
                 r.constant_fold();
                 if let (Expr::Int(i), Expr::Int(j)) = (l.as_ref(), r.as_ref()) {
-                    *self = Expr::Int(Located::new(i.val() % j.val(), i.loc().merge(j.loc())));
+                    if let Some(k) = i.val().checked_rem(*j.val()) {
+                        *self = Expr::Int(Located::new(k, i.loc().merge(j.loc())));
+                    }
                 }
             }
             Self::Uminus(c, _) => {
                 c.constant_fold();
                 match c.as_ref() {
-                    Expr::Int(i) => *self = Expr::Int(Located::new(-i.val(), i.loc().clone())),
+                    Expr::Int(i) => {
+                        if let Some(k) = i.val().checked_neg() {
+                            *self = Expr::Int(Located::new(k, i.loc().clone()));
+                        }
+                    }
                     Expr::Float(i) => *self = Expr::Float(Located::new(-i.val(), i.loc().clone())),
                     _ => {}
                 }
@@ -378,11 +394,8 @@ impl Expr {
             | Expr::Sqrt(_, Some(a)) => a.expr_type,
             Expr::Id(_) => ExprType::Int, // unaffected id can only be label at this point so it's
             // an int
-            _ => {
-                return Err(Error::Simple(
-                    "Can't know type of unanotated node".to_owned(),
-                ))
-            }
+            // `anotate` gives every other node its type
+            _ => return Err(Error::ShouldNeverBeThere),
         })
     }
 
@@ -408,8 +421,9 @@ impl Expr {
                 let l_type = l.get_type()?;
                 let r_type = r.get_type()?;
                 if l_type != r_type {
-                    return Err(Error::Simple(
+                    return Err(Error::Type(
                         "Params of operation are expected to be the same type".to_owned(),
+                        l.loc().merge(&r.loc()),
                     ));
                 }
                 *a = Some(ExprAnnotation { expr_type: l_type })
@@ -425,13 +439,15 @@ impl Expr {
                 let l_type = l.get_type()?;
                 let r_type = r.get_type()?;
                 if l_type != r_type {
-                    return Err(Error::Simple(
+                    return Err(Error::Type(
                         "Params of binary operation are expected to be the same type".to_owned(),
+                        l.loc().merge(&r.loc()),
                     ));
                 }
                 if l_type != ExprType::Int {
-                    return Err(Error::Simple(
+                    return Err(Error::Type(
                         "Params of binary operation are expected to be of type int".to_owned(),
+                        l.loc().merge(&r.loc()),
                     ));
                 }
                 *a = Some(ExprAnnotation { expr_type: l_type })
@@ -447,8 +463,9 @@ impl Expr {
                 let l_type = l.get_type()?;
                 let r_type = r.get_type()?;
                 if l_type != r_type {
-                    return Err(Error::Simple(
+                    return Err(Error::Type(
                         "Params of comparison are expected to be the same type".to_owned(),
+                        l.loc().merge(&r.loc()),
                     ));
                 }
                 *a = Some(ExprAnnotation {
@@ -470,8 +487,9 @@ impl Expr {
             Expr::Sqrt(l, ref mut a) | Expr::Sin(l, ref mut a) | Expr::Cos(l, ref mut a) => {
                 l.anotate()?;
                 if l.get_type()? != ExprType::Float {
-                    return Err(Error::Simple(
+                    return Err(Error::Type(
                         "Param of Sqrt, Sin, or Cos is expected to be a float".to_owned(),
+                        l.loc(),
                     ));
                 }
                 *a = Some(ExprAnnotation {
@@ -492,10 +510,10 @@ impl Expr {
                 instructions.push(Instr::Call("ins_42".to_string().into(), vec![self.clone()]));
             }
             Self::Float(_) => {
-                instructions.push(Instr::Call("ins_43".to_string().into(), vec![self.clone()]));
+                instructions.push(Instr::Call("ins_44".to_string().into(), vec![self.clone()]));
             }
             Self::VarFloat(_) => {
-                instructions.push(Instr::Call("ins_43".to_string().into(), vec![self.clone()]));
+                instructions.push(Instr::Call("ins_44".to_string().into(), vec![self.clone()]));
             }
             Self::Uminus(e, Some(a)) => {
                 instructions.extend(e.instructions()?);
@@ -509,9 +527,9 @@ impl Expr {
                     _ => panic!("Can't negate a non number"),
                 }
             }
-            Self::Not(e, Some(a)) => {
+            Self::Not(e, Some(_)) => {
                 instructions.extend(e.instructions()?);
-                match a.expr_type {
+                match e.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_71".to_string().into(), vec![]))
                     }
@@ -573,10 +591,11 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Gt(e1, e2, Some(a)) => {
+            Self::Gt(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                // the result is an int, the opcode depends on the operands
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_67".to_string().into(), vec![]))
                     }
@@ -586,10 +605,10 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Ge(e1, e2, Some(a)) => {
+            Self::Ge(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_69".to_string().into(), vec![]))
                     }
@@ -599,10 +618,10 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Lt(e1, e2, Some(a)) => {
+            Self::Lt(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_63".to_string().into(), vec![]))
                     }
@@ -612,10 +631,10 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Le(e1, e2, Some(a)) => {
+            Self::Le(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_65".to_string().into(), vec![]))
                     }
@@ -625,10 +644,10 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Eq(e1, e2, Some(a)) => {
+            Self::Eq(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_59".to_string().into(), vec![]))
                     }
@@ -638,10 +657,10 @@ impl Expr {
                     _ => panic!("Can't subtract a non number"),
                 }
             }
-            Self::Ne(e1, e2, Some(a)) => {
+            Self::Ne(e1, e2, Some(_)) => {
                 instructions.extend(e1.instructions()?);
                 instructions.extend(e2.instructions()?);
-                match a.expr_type {
+                match e1.get_type()? {
                     ExprType::Int => {
                         instructions.push(Instr::Call("ins_61".to_string().into(), vec![]))
                     }
@@ -695,6 +714,7 @@ impl Expr {
             }
             Self::Id(i) => {
                 report_error_ext(
+                    ErrorCode::UnresolvedIdentifier,
                     i.loc(),
                     &format!("Unresolved identifier `{}`", i.val()),
                     "Unresolved identifier",
@@ -702,7 +722,11 @@ impl Expr {
                 panic!("");
             }
             Self::Str(s) => {
-                report_error(s.loc(), "Can't push a string on the stack");
+                report_error(
+                    ErrorCode::TypeMismatch,
+                    s.loc(),
+                    "Can't push a string on the stack",
+                );
                 panic!("");
             }
             Self::Vararg(_) => panic!("Can't push a vararg on the stack"),
@@ -760,7 +784,7 @@ impl Expr {
             Self::VarInt(a) => Self::VarInt(a.relocate(loc.clone())),
             Self::Float(a) => Self::Float(a.relocate(loc.clone())),
             Self::VarFloat(a) => Self::VarFloat(a.relocate(loc.clone())),
-            Self::Str(a) => Self::Id(a.relocate(loc.clone())),
+            Self::Str(a) => Self::Str(a.relocate(loc.clone())),
             _ => self.clone(),
         }
     }
@@ -769,7 +793,7 @@ impl Expr {
         match self {
             Self::Id(s) => {
                 if s.val() == id {
-                    *self = to.clone();
+                    *self = to.relocate(s.loc());
                 }
             }
             Self::Add(a, b, _)
@@ -811,6 +835,43 @@ impl Expr {
         }
     }
 
+    /// Calls `f` on every node of the expression, children first.
+    pub fn visit_mut(&mut self, f: &mut dyn FnMut(&mut Expr)) {
+        match self {
+            Self::Add(a, b, _)
+            | Self::Sub(a, b, _)
+            | Self::Mul(a, b, _)
+            | Self::Div(a, b, _)
+            | Self::Ne(a, b, _)
+            | Self::Eq(a, b, _)
+            | Self::Gt(a, b, _)
+            | Self::Ge(a, b, _)
+            | Self::Lt(a, b, _)
+            | Self::Le(a, b, _)
+            | Self::BinOr(a, b, _)
+            | Self::BinAnd(a, b, _)
+            | Self::Xor(a, b, _)
+            | Self::Or(a, b, _)
+            | Self::And(a, b, _)
+            | Self::Modulo(a, b, _) => {
+                a.visit_mut(f);
+                b.visit_mut(f);
+            }
+            Self::Uminus(a, _)
+            | Self::Not(a, _)
+            | Self::Sin(a, _)
+            | Self::Cos(a, _)
+            | Self::Sqrt(a, _) => a.visit_mut(f),
+            Self::Vararg(va) => {
+                for v in va {
+                    v.visit_mut(f);
+                }
+            }
+            _ => {}
+        }
+        f(self);
+    }
+
     pub fn replace_all_id(&mut self, ids: &std::collections::HashMap<String, Expr>) {
         for (id, ex) in ids {
             self.replace_id(id, ex);
@@ -859,10 +920,18 @@ fn resolve_expr(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
             E::Str(args[0].clone().token().strn_loc())
         }
         "Id" => {
-            if args.len() != 1 {
+            if args.len() != 2 {
                 return Err(Error::Grammar("Expr::Id takes 2 param".to_owned()));
             }
-            E::Id(args[0].clone().token().id_loc())
+            let id = args[0].clone().token().id_loc();
+            match &args[1] {
+                // `Enum::VARIANT`
+                AstNode::Token(t) => E::Id(Located::new(
+                    format!("{}::{}", id.val(), t.id()),
+                    id.loc().merge(t.loc()),
+                )),
+                _ => E::Id(id),
+            }
         }
         "Add" => {
             if args.len() != 2 {
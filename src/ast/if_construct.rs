@@ -1,4 +1,4 @@
-use crate::error::{report_error_ext, Error};
+use crate::error::{report_error_ext, Error, ErrorCode};
 
 use super::*;
 
@@ -51,14 +51,15 @@ pub fn desugar(
     e.constant_fold();
     if e.get_type()? != ExprType::Int {
         report_error_ext(
+            ErrorCode::NonIntCondition,
             &e.loc(),
             "Condition expression should be of type int",
             "This expression should have type int",
         );
         return Err(Error::Simple("If condition must be Int".to_owned()));
     }
-    if e.is_primitive() && !e.is_var() {
-        let i = e.int();
+    // an identifier left is unresolved, reported with the pushed expression
+    if let Expr::Int(i) = &e {
         if *i.val() != 0 {
             new_instructions.extend(if_bloc);
         } else {
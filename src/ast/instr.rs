@@ -1,4 +1,8 @@
-use crate::error::{report_error_ext, Error};
+use crate::{
+    error::{report_error_ext, Error, ErrorCode},
+    lexer::Location,
+    scpt::{self, Encoding},
+};
 
 use super::*;
 
@@ -29,6 +33,8 @@ pub enum Instr {
 }
 
 impl Instr {
+    /// `name(int, float)` for a call, `?` standing for an argument of
+    /// unknown type.
     pub fn signature(&self) -> Result<String, Error> {
         match self {
             Self::Call(n, e) => {
@@ -38,11 +44,12 @@ impl Instr {
                     if i != 0 {
                         s.push_str(", ");
                     }
-                    match ex.get_type().unwrap() {
-                        ExprType::Int => s.push_str("int"),
-                        ExprType::Float => s.push_str("float"),
-                        ExprType::String => s.push_str("str"),
-                        ExprType::Vararg => s.push_str("..."),
+                    match ex.get_type() {
+                        Ok(ExprType::Int) => s.push_str("int"),
+                        Ok(ExprType::Float) => s.push_str("float"),
+                        Ok(ExprType::String) => s.push_str("str"),
+                        Ok(ExprType::Vararg) => s.push_str("..."),
+                        Err(_) => s.push('?'),
                     }
                 }
                 s.push(')');
@@ -54,7 +61,31 @@ impl Instr {
         }
     }
 
-    pub fn size(&self) -> usize {
+    /// The blocks directly inside the instruction.
+    pub fn blocks(&self) -> Vec<&Vec<Instr>> {
+        match self {
+            Self::Bloc(l) | Self::Loop(l) | Self::While(_, l) | Self::DoWhile(_, l) => vec![l],
+            Self::If(_, l1, l2) => vec![l1, l2],
+            _ => vec![],
+        }
+    }
+
+    /// Where the instruction starts in the source, if it was written there.
+    pub fn loc(&self) -> Option<Location> {
+        match self {
+            Self::Label(l) | Self::Call(l, _) | Self::Affect(l, _) => Some(l.loc().clone()),
+            Self::VarInt(l, _) | Self::VarFloat(l, _) => Some(l.loc().clone()),
+            Self::TimeLabel(t, _) => Some(t.loc().clone()),
+            Self::RankLabel(r) => Some(r.loc().clone()),
+            Self::PushExpr(e) | Self::If(e, _, _) | Self::While(e, _) => Some(e.loc()),
+            Self::Bloc(l) | Self::Loop(l) => l.iter().find_map(|i| i.loc()),
+            Self::DoWhile(e, l) => l.iter().find_map(|i| i.loc()).or(Some(e.loc())),
+            Self::Break | Self::Continue => None,
+        }
+    }
+
+    /// Size of the instruction in the binary, strings being encoded with `encoding`.
+    pub fn size(&self, encoding: Encoding) -> usize {
         match self {
             Self::Label(_) => 0,
             Self::TimeLabel(_, _) => 0,
@@ -64,15 +95,19 @@ impl Instr {
                 for e in v {
                     s += match e {
                         Expr::Str(st) => {
-                            let slen = st.val().len();
-                            slen + 4 + (4 - (slen % 4))
+                            let slen = encoding
+                                .encode(st.val())
+                                .map_or(st.val().len(), |b| b.len());
+                            scpt::str_arg_size(slen)
                         }
+                        // type tag and value for each parameter
+                        Expr::Vararg(va) => va.len() * 8,
                         _ => 4,
                     };
                 }
                 s
             }
-            Self::Bloc(v) => v.iter().map(|i| i.size()).sum(),
+            Self::Bloc(v) => v.iter().map(|i| i.size(encoding)).sum(),
             _ => panic!("Should not call size on this Instr type"),
         }
     }
@@ -96,11 +131,13 @@ fn resolve_instr(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
                             Ok(_) => {}
                             Err(_) => {
                                 report_error_ext(
+                                    ErrorCode::UnknownInstruction,
                                     id.loc(),
                                     &format!("instruction `{}` does not exist", id.val()),
                                     "unknown instruction",
                                 );
-                                return Err(Error::Simple("instruction does not exist".to_owned()));
+                                // left out like a syntax error, the rest of the file is checked
+                                return Ok(AstNode::None);
                             }
                         }
                     }
@@ -221,12 +258,14 @@ fn resolve_instr(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
             Instr::Continue
         }
         "Return" => {
-            assert!(args.is_empty());
-            Instr::Call("ins_10".to_string().into(), vec![])
+            assert!(args.len() == 1);
+            let loc = args[0].clone().token().loc().clone();
+            Instr::Call(Located::new("ins_10".to_string(), loc), vec![])
         }
         "Delete" => {
-            assert!(args.is_empty());
-            Instr::Call("ins_1".to_string().into(), vec![])
+            assert!(args.len() == 1);
+            let loc = args[0].clone().token().loc().clone();
+            Instr::Call(Located::new("ins_1".to_string(), loc), vec![])
         }
         "If" => {
             assert!(args.len() == 3);
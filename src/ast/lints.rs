@@ -0,0 +1,166 @@
+//! The warnings about code that compiles but likely doesn't do what was meant.
+
+use std::collections::HashSet;
+
+use crate::{
+    error::{emit, warn, Diagnostic, Severity},
+    lint::Lint,
+};
+
+use super::*;
+
+fn ends_sub(i: &Instr) -> Option<&Located<String>> {
+    match i {
+        Instr::Call(name, _) if name.val() == "ins_10" || name.val() == "ins_1" => Some(name),
+        _ => None,
+    }
+}
+
+/// Warns about the code after a `return` or a `delete`, up to the next
+/// label, which a `goto` can reach.
+pub fn unreachable_code(instrs: &[Instr]) {
+    let mut end: Option<&Located<String>> = None;
+    let mut reported = false;
+    for i in instrs {
+        if let Instr::Label(_) = i {
+            end = None;
+            reported = false;
+            continue;
+        }
+        if let (Some(e), false, Some(loc)) = (end, reported, i.loc()) {
+            let d = Diagnostic::warning(
+                Lint::UnreachableCode,
+                &loc,
+                "unreachable code",
+                "never executed",
+            );
+            let what = if e.val() == "ins_1" {
+                "delete"
+            } else {
+                "return"
+            };
+            emit(d.with_note(Diagnostic::note(
+                e.loc(),
+                &format!("the code after this {what} is unreachable"),
+            )));
+            reported = true;
+        }
+        for b in i.blocks() {
+            unreachable_code(b);
+        }
+        if end.is_none() {
+            end = ends_sub(i);
+        }
+    }
+}
+
+/// Warns about the `if` and `while` conditions that fold to a constant.
+/// The identifiers have to be replaced before.
+pub fn constant_conditions(instrs: &[Instr]) {
+    for i in instrs {
+        let cond = match i {
+            Instr::If(e, _, _) => Some((e, "if")),
+            Instr::While(e, _) | Instr::DoWhile(e, _) => Some((e, "while")),
+            _ => None,
+        };
+        if let Some((cond, what)) = cond {
+            let mut folded = cond.clone();
+            if folded.anotate().is_ok() {
+                folded.constant_fold();
+            }
+            if let Expr::Int(v) = folded {
+                let always = if *v.val() != 0 { "true" } else { "false" };
+                let mut d = Diagnostic::warning(
+                    Lint::ConstantConditions,
+                    &cond.loc(),
+                    &format!("`{what}` condition is always {always}"),
+                    &format!("always {always}"),
+                );
+                if what == "while" && always == "true" {
+                    d = d.with_note(Diagnostic::simple(
+                        Severity::Note,
+                        "use `loop` for a loop that only ends with `break`",
+                    ));
+                }
+                emit(d);
+            }
+        }
+        for b in i.blocks() {
+            constant_conditions(b);
+        }
+    }
+}
+
+/// Warns about the time labels that go back before the time reached by the
+/// previous ones, `-N:` ones always going back. The time after a label or a
+/// block that changes it is unknown.
+pub fn backwards_time_labels(instrs: &[Instr], time: &mut Option<i32>) {
+    for i in instrs {
+        match i {
+            Instr::TimeLabel(t, TimeLabelKind::Set) => {
+                if let Some(now) = *time {
+                    if *t.val() < now {
+                        warn(
+                            Lint::BackwardsTimeLabels,
+                            t.loc(),
+                            &format!("time label `{}:` goes back from time {now}", t.val()),
+                            "before the current time",
+                        );
+                    }
+                }
+                *time = Some(*t.val());
+            }
+            Instr::TimeLabel(t, TimeLabelKind::Add) => *time = time.map(|n| n + t.val()),
+            Instr::TimeLabel(t, TimeLabelKind::Sub) => {
+                if *t.val() > 0 {
+                    let from = time.map_or("in time".to_owned(), |n| format!("from time {n}"));
+                    warn(
+                        Lint::BackwardsTimeLabels,
+                        t.loc(),
+                        &format!("time label `-{}:` goes back {from}", t.val()),
+                        "before the current time",
+                    );
+                }
+                *time = time.map(|n| n - t.val());
+            }
+            Instr::Label(_) => *time = None,
+            Instr::Bloc(l) => backwards_time_labels(l, time),
+            _ => {
+                let start = *time;
+                for b in i.blocks() {
+                    let mut t = start;
+                    backwards_time_labels(b, &mut t);
+                    if t != start {
+                        *time = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Warns about the subs whose name is in no string argument of the script,
+/// `main` excepted. The instructions have to be resolved before.
+pub fn unused_subs(ecl: &Ecl) {
+    let mut names = HashSet::new();
+    for s in &ecl.subs {
+        for i in &s.instructions {
+            if let Instr::Call(_, args) = i {
+                names.extend(args.iter().filter_map(|a| match a {
+                    Expr::Str(s) => Some(s.val()),
+                    _ => None,
+                }));
+            }
+        }
+    }
+    for s in &ecl.subs {
+        if s.name.val() != "main" && !names.contains(s.name.val()) {
+            warn(
+                Lint::UnusedSubs,
+                s.name.loc(),
+                &format!("sub `{}` is never called", s.name.val()),
+                "never called",
+            );
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::lexer::Location;
+use crate::{
+    error::{report_error_ext, Error, ErrorCode},
+    lexer::{FileId, Location},
+    scpt::Encoding,
+};
 
 use std::ops::*;
 
@@ -26,12 +30,34 @@ impl<T: Clone> Located<T> {
         Self(self.0.clone(), l)
     }
 
-    pub fn relocate_into(mut self, l: Location) -> Self {
+    pub fn _relocate_into(mut self, l: Location) -> Self {
         self.1 = l;
         self
     }
 }
 
+impl Located<String> {
+    /// Encodes a string of the source, reporting the first character
+    /// `encoding` can't represent.
+    pub fn encode(&self, encoding: Encoding) -> Result<Vec<u8>, Error> {
+        encoding.encode(&self.0).map_err(|r| {
+            let c = &self.0[r.clone()];
+            // point at the character when the literal has no escape sequence
+            let mut loc = self.1.clone();
+            if loc.span.len() == self.0.len() + 2 {
+                loc.span = loc.span.start + 1 + r.start..loc.span.start + 1 + r.end;
+            }
+            report_error_ext(
+                ErrorCode::Unencodable,
+                &loc,
+                &format!("`{c}` can't be encoded in {encoding}"),
+                "unrepresentable character",
+            );
+            Error::Simple("string can't be encoded".to_owned())
+        })
+    }
+}
+
 impl<T: Clone + Add<Output = T>> Add for Located<T> {
     type Output = Located<T>;
 
@@ -69,6 +95,7 @@ impl<T: Clone> From<T> for Located<T> {
         Self(
             t,
             Location {
+                file: FileId::default(),
                 line: 0,
                 span: 0..1,
             },
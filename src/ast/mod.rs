@@ -1,11 +1,14 @@
 mod builtin_idents;
+mod constant;
 mod ecl;
 mod expr;
 mod if_construct;
 mod instr;
+mod lints;
 mod located;
 mod loop_construct;
 mod node;
+mod printer;
 mod special_ast_nodes;
 mod sub;
 mod tok_name_for_error;
@@ -13,11 +16,14 @@ mod token;
 mod tokens_to_vals;
 mod variables;
 mod while_construct;
+pub use builtin_idents::{builtins, PRELUDE};
+pub use constant::{Const, Enum};
 pub use ecl::Ecl;
 pub use expr::{Expr, ExprType};
 pub use instr::{Instr, TimeLabelKind};
 pub use located::Located;
 pub use node::AstNode;
+pub use printer::{format_ecl, print_ecl, Comment, Layout};
 pub use sub::{Param, Sub};
 pub use tok_name_for_error::tok_name_for_error;
 pub use token::Token;
@@ -28,6 +34,7 @@ use crate::parser::ast::AstResolver;
 pub fn fill_executor(resolver: &mut AstResolver<AstNode>) {
     special_ast_nodes::fill_executor(resolver);
     ecl::fill_executor(resolver);
+    constant::fill_executor(resolver);
     sub::fill_executor(resolver);
     instr::fill_executor(resolver);
     expr::fill_executor(resolver);
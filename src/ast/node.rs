@@ -7,6 +7,8 @@ use magic_unwrapper::EnumUnwrap;
 pub enum AstNode {
     Ecl(Ecl),
     Sub(Sub),
+    Constant(Const),
+    EnumDecl(Enum),
     Param(Param),
     Instr(Instr),
     Expr(Expr),
@@ -34,6 +36,12 @@ impl AstNode {
 
 impl NeededForAstNode for AstNode {
     fn from_token(tok: &crate::lexer::Token<&str>) -> Result<Self, Error> {
-        Ok(Self::Token(Token::from(tok)))
+        Ok(Self::Token(Token::new(tok)?))
+    }
+
+    /// Nothing: the lists leave it out, so the rest of the file can be
+    /// checked as if the erroneous part wasn't there.
+    fn from_error(_nt: &str) -> Self {
+        Self::None
     }
 }
@@ -0,0 +1,635 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::lexer::Location;
+
+use super::{ecl::Include, *};
+
+// Precedence levels of the expression grammar, from loosest to tightest.
+const PREC_OR: u8 = 1;
+const PREC_AND: u8 = 2;
+const PREC_BINOR: u8 = 3;
+const PREC_XOR: u8 = 4;
+const PREC_BINAND: u8 = 5;
+const PREC_EQ: u8 = 6;
+const PREC_CMP: u8 = 7;
+const PREC_ADD: u8 = 8;
+const PREC_MUL: u8 = 9;
+const PREC_UNARY: u8 = 10;
+const PREC_PRIMARY: u8 = 11;
+
+pub fn float_lit(f: f32) -> String {
+    let s = format!("{:?}", f);
+    if s.contains('e') || s.contains("inf") || s.contains("NaN") {
+        s
+    } else {
+        format!("{s}f")
+    }
+}
+
+pub fn int_lit(i: i32) -> String {
+    if i == i32::MIN {
+        // the literal 2147483648 doesn't fit in an int
+        "(-2147483647 - 1)".to_owned()
+    } else {
+        format!("{i}")
+    }
+}
+
+pub fn str_lit(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn is_ident(s: &str) -> bool {
+    let mut chrs = s.chars();
+    match chrs.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chrs.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A comment of the source, which the formatter puts back where it was.
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub text: String,
+    pub line: usize,
+    /// Byte of the line where it starts.
+    pub col: usize,
+    /// Number of braces the comment is in.
+    pub depth: usize,
+    /// After some code of its line.
+    pub trailing: bool,
+}
+
+/// What the formatter keeps of the source besides its AST: the comments,
+/// the empty lines and the literals as written.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub lines: Vec<String>,
+    pub comments: Vec<Comment>,
+    pub empty_lines: BTreeSet<usize>,
+}
+
+impl Layout {
+    /// The source at `loc`, if it is on one line.
+    fn written(&self, loc: &Location) -> Option<&str> {
+        self.lines.get(loc.line)?.get(loc.span.clone())
+    }
+
+    /// The literal at `loc` as written, to keep its base or its escapes,
+    /// if it starts like one.
+    fn literal(&self, loc: &Location, start: impl Fn(char) -> bool) -> Option<String> {
+        let text = self.written(loc)?;
+        text.starts_with(start).then(|| text.to_owned())
+    }
+}
+
+fn rank_label(r: u8) -> String {
+    if r == 255 {
+        return "!*:".to_owned();
+    }
+    let mut s = String::from("!");
+    for (i, c) in "enhlxo".chars().enumerate() {
+        if r & (1 << i) != 0 {
+            s.push(c);
+        }
+    }
+    s.push(':');
+    if r & 192 != 192 {
+        s.push_str(&format!(" // rank mask {r} can't be represented"));
+    }
+    s
+}
+
+fn expr_prec(e: &Expr) -> u8 {
+    match e {
+        Expr::Or(..) => PREC_OR,
+        Expr::And(..) => PREC_AND,
+        Expr::BinOr(..) => PREC_BINOR,
+        Expr::Xor(..) => PREC_XOR,
+        Expr::BinAnd(..) => PREC_BINAND,
+        Expr::Eq(..) | Expr::Ne(..) => PREC_EQ,
+        Expr::Lt(..) | Expr::Le(..) | Expr::Gt(..) | Expr::Ge(..) => PREC_CMP,
+        Expr::Add(..) | Expr::Sub(..) => PREC_ADD,
+        Expr::Mul(..) | Expr::Div(..) | Expr::Modulo(..) => PREC_MUL,
+        Expr::Uminus(..) | Expr::Not(..) => PREC_UNARY,
+        Expr::Int(i) if *i.val() < 0 => PREC_UNARY,
+        Expr::Float(f) if f.val().is_sign_negative() => PREC_UNARY,
+        _ => PREC_PRIMARY,
+    }
+}
+
+fn expr_paren(e: &Expr, min_prec: u8, l: &Layout) -> String {
+    let s = expr(e, l);
+    if expr_prec(e) < min_prec {
+        format!("({s})")
+    } else {
+        s
+    }
+}
+
+fn binop(a: &Expr, op: &str, b: &Expr, prec: u8, l: &Layout) -> String {
+    // operators are left associative
+    format!(
+        "{} {} {}",
+        expr_paren(a, prec, l),
+        op,
+        expr_paren(b, prec + 1, l)
+    )
+}
+
+/// `e` as source, its literals as written in `l`.
+pub fn expr(e: &Expr, l: &Layout) -> String {
+    match e {
+        Expr::Int(i) => l
+            .literal(i.loc(), |c| c.is_ascii_digit() || c == '\'')
+            .unwrap_or_else(|| int_lit(*i.val())),
+        Expr::Float(f) => l
+            .literal(f.loc(), |c| c.is_ascii_digit() || c == '.')
+            .unwrap_or_else(|| float_lit(*f.val())),
+        Expr::Str(s) => l
+            .literal(s.loc(), |c| c == '"')
+            .unwrap_or_else(|| str_lit(s.val())),
+        Expr::Id(i) => i.val().clone(),
+        Expr::VarInt(i) => format!("[{}]", i.val()),
+        Expr::VarFloat(f) => format!("[{}]", float_lit(*f.val())),
+        Expr::Vararg(va) => args(va, l),
+        Expr::Or(a, b, _) => binop(a, "||", b, PREC_OR, l),
+        Expr::And(a, b, _) => binop(a, "&&", b, PREC_AND, l),
+        Expr::BinOr(a, b, _) => binop(a, "|", b, PREC_BINOR, l),
+        Expr::Xor(a, b, _) => binop(a, "^", b, PREC_XOR, l),
+        Expr::BinAnd(a, b, _) => binop(a, "&", b, PREC_BINAND, l),
+        Expr::Eq(a, b, _) => binop(a, "==", b, PREC_EQ, l),
+        Expr::Ne(a, b, _) => binop(a, "!=", b, PREC_EQ, l),
+        Expr::Lt(a, b, _) => binop(a, "<", b, PREC_CMP, l),
+        Expr::Le(a, b, _) => binop(a, "<=", b, PREC_CMP, l),
+        Expr::Gt(a, b, _) => binop(a, ">", b, PREC_CMP, l),
+        Expr::Ge(a, b, _) => binop(a, ">=", b, PREC_CMP, l),
+        Expr::Add(a, b, _) => binop(a, "+", b, PREC_ADD, l),
+        Expr::Sub(a, b, _) => binop(a, "-", b, PREC_ADD, l),
+        Expr::Mul(a, b, _) => binop(a, "*", b, PREC_MUL, l),
+        Expr::Div(a, b, _) => binop(a, "/", b, PREC_MUL, l),
+        Expr::Modulo(a, b, _) => binop(a, "%", b, PREC_MUL, l),
+        // unary operators only take a primitive expression
+        Expr::Uminus(e, _) => format!("-{}", expr_paren(e, PREC_PRIMARY, l)),
+        Expr::Not(e, _) => format!("!{}", expr_paren(e, PREC_PRIMARY, l)),
+        Expr::Sin(e, _) => format!("sin({})", expr(e, l)),
+        Expr::Cos(e, _) => format!("cos({})", expr(e, l)),
+        Expr::Sqrt(e, _) => format!("sqrt({})", expr(e, l)),
+    }
+}
+
+fn args(a: &[Expr], l: &Layout) -> String {
+    a.iter().map(|e| expr(e, l)).collect::<Vec<_>>().join(", ")
+}
+
+/// The kinds of top-level items, in the order of the declarations.
+enum Item<'a> {
+    Include(&'a Include),
+    Const(&'a Const),
+    Enum(&'a Enum),
+    Sub(&'a Sub),
+}
+
+/// Where `i` starts in the source, `goto` being at its label.
+fn start(i: &Instr) -> Option<Location> {
+    match i {
+        Instr::Call(n, a) if n.val() == "ins_12" => match a.first() {
+            Some(Expr::Id(l)) => Some(l.loc().clone()),
+            _ => i.loc(),
+        },
+        _ => i.loc(),
+    }
+}
+
+/// Pretty printer from the AST back to eclc source.
+///
+/// Given the layout of the source, the comments and the empty lines are
+/// put back between the instructions and the declarations.
+pub struct Printer {
+    out: String,
+    indent: usize,
+    layout: Layout,
+    comments: VecDeque<Comment>,
+    /// Last line of the source printed.
+    last_line: usize,
+    /// Start of what follows the block being printed, the comments after it
+    /// being left for later.
+    limit: Option<Location>,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Self::with_layout(Layout::default())
+    }
+
+    pub fn with_layout(layout: Layout) -> Self {
+        Self {
+            out: String::new(),
+            indent: 0,
+            comments: layout.comments.iter().cloned().collect(),
+            layout,
+            last_line: 0,
+            limit: None,
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn line(&mut self, s: &str) {
+        self.out.push_str(&"    ".repeat(self.indent));
+        self.out.push_str(s);
+        self.out.push('\n');
+    }
+
+    /// Labels are printed one level to the left of the instructions.
+    fn outdented_line(&mut self, s: &str) {
+        self.out
+            .push_str(&"    ".repeat(self.indent.saturating_sub(1)));
+        self.out.push_str(s);
+        self.out.push('\n');
+    }
+
+    /// An empty line, unless at the start of the file or of a block.
+    fn blank_line(&mut self) {
+        if !(self.out.is_empty() || self.out.ends_with("\n\n") || self.out.ends_with("{\n")) {
+            self.out.push('\n');
+        }
+    }
+
+    /// Keeps an empty line of the source before `line`.
+    fn source_line(&mut self, line: usize) {
+        if line > self.last_line + 1
+            && self
+                .layout
+                .empty_lines
+                .range(self.last_line + 1..line)
+                .next()
+                .is_some()
+        {
+            self.blank_line();
+        }
+        self.last_line = self.last_line.max(line);
+    }
+
+    fn comment(&mut self, c: Comment) {
+        if c.trailing && c.line == self.last_line && self.out.ends_with('\n') {
+            self.out.pop();
+            self.out.push(' ');
+            self.out.push_str(&c.text);
+            self.out.push('\n');
+        } else {
+            self.source_line(c.line);
+            self.line(&c.text);
+        }
+        self.last_line = c.line + c.text.matches('\n').count();
+    }
+
+    /// Prints the comments before `loc` which are in at most `depth` braces.
+    fn comments_before(&mut self, loc: &Location, depth: usize) {
+        while let Some(c) = self.comments.front() {
+            if c.depth > depth || (c.line, c.col) >= (loc.line, loc.span.start) {
+                break;
+            }
+            let c = self.comments.pop_front().unwrap();
+            self.comment(c);
+        }
+    }
+
+    /// Prints the comments left in the block at `depth`, before its end.
+    fn comments_to_end(&mut self, depth: usize) {
+        let limit = self.limit.clone();
+        let in_block = move |c: &Comment| {
+            c.depth >= depth
+                && (limit.as_ref()).is_none_or(|l| (c.line, c.col) < (l.line, l.span.start))
+        };
+        while self.comments.front().is_some_and(&in_block) {
+            let c = self.comments.pop_front().unwrap();
+            self.comment(c);
+        }
+    }
+
+    /// Prints the comments after the end of the previous item on its line,
+    /// before the item at `next`.
+    fn comments_after_item(&mut self, next: Option<&Location>) {
+        while let Some(c) = self.comments.front() {
+            let before = next.is_none_or(|l| (c.line, c.col) < (l.line, l.span.start));
+            if !(c.trailing && c.depth == 0 && before) {
+                break;
+            }
+            let c = self.comments.pop_front().unwrap();
+            // the line where the item ends, which isn't in its AST
+            self.last_line = c.line;
+            self.comment(c);
+        }
+    }
+
+    /// Prints the comments left in a list of `ecli` or `anmi` after its
+    /// line, before what comes at `next`.
+    fn comments_in_list(&mut self, next: Option<&Location>) {
+        while let Some(c) = self.comments.front() {
+            let before = next.is_none_or(|l| (c.line, c.col) < (l.line, l.span.start));
+            if !(c.depth > 0 && before) {
+                break;
+            }
+            let c = self.comments.pop_front().unwrap();
+            self.comment(c);
+        }
+    }
+
+    fn expr(&self, e: &Expr) -> String {
+        expr(e, &self.layout)
+    }
+
+    fn args(&self, a: &[Expr]) -> String {
+        args(a, &self.layout)
+    }
+
+    fn str_list(&mut self, kw: &str, l: &[Located<String>]) {
+        if l.is_empty() {
+            self.line(&format!("{kw} {{}}"));
+        } else {
+            let l: Vec<_> = l
+                .iter()
+                .map(|s| {
+                    self.layout
+                        .literal(s.loc(), |c| c == '"')
+                        .unwrap_or_else(|| str_lit(s.val()))
+                })
+                .collect();
+            self.line(&format!("{kw} {{ {} }}", l.join(", ")));
+        }
+    }
+
+    pub fn ecl(&mut self, ecl: &Ecl) {
+        // the decompiler always prints the lists, the formatter only those written
+        let formatting = !self.layout.lines.is_empty();
+        // the constants of the enums are printed with them
+        let variants: Vec<_> = ecl
+            .enums
+            .iter()
+            .flat_map(|e| e.variants.iter().map(|v| v.0.loc()))
+            .collect();
+        let mut items: Vec<(&Location, Item)> = Vec::new();
+        items.extend(
+            ecl.includes
+                .iter()
+                .map(|i| (i.path.loc(), Item::Include(i))),
+        );
+        items.extend(
+            ecl.consts
+                .iter()
+                .filter(|c| !variants.contains(&c.name.loc()))
+                .map(|c| (c.name.loc(), Item::Const(c))),
+        );
+        items.extend(ecl.enums.iter().map(|e| (e.name.loc(), Item::Enum(e))));
+        items.extend(ecl.subs.iter().map(|s| (s.name.loc(), Item::Sub(s))));
+        items.sort_by_key(|(loc, _)| (loc.line, loc.span.start));
+
+        let mut header = false;
+        let lists = [("ecli", &ecl.ecli), ("anmi", &ecl.anmi)];
+        for (n, (kw, l)) in lists.iter().enumerate() {
+            if !(formatting && l.is_empty()) {
+                if let Some(last) = l.last() {
+                    self.comments_before(last.loc(), usize::MAX);
+                    self.source_line(last.loc().line);
+                }
+                self.str_list(kw, l);
+                header = true;
+            }
+            let next = lists[n + 1..]
+                .iter()
+                .find_map(|(_, l)| l.first().map(|s| s.loc()))
+                .or(items.first().map(|(loc, _)| *loc));
+            self.comments_in_list(next);
+        }
+
+        // subs and enums are separated by an empty line, like different kinds
+        // of declarations
+        let mut prev = None;
+        for (n, &(loc, ref item)) in items.iter().enumerate() {
+            self.limit = items.get(n + 1).map(|(l, _)| (*l).clone());
+            let kind = match item {
+                Item::Include(_) => 0,
+                Item::Const(_) => 1,
+                Item::Enum(_) => 2,
+                Item::Sub(_) => 3,
+            };
+            self.comments_after_item(Some(loc));
+            if prev.map_or(header, |p| p != kind || kind >= 2) {
+                self.blank_line();
+            }
+            prev = Some(kind);
+            self.comments_before(loc, 0);
+            self.source_line(loc.line);
+            match item {
+                Item::Include(i) => {
+                    let path = self
+                        .layout
+                        .literal(i.path.loc(), |c| c == '"')
+                        .unwrap_or_else(|| str_lit(i.path.val()));
+                    self.line(&format!("include {path};"));
+                }
+                Item::Const(c) => self.constant(c),
+                Item::Enum(e) => self.enumeration(e),
+                Item::Sub(s) => self.sub(s),
+            }
+        }
+        self.limit = None;
+        self.comments_after_item(None);
+        self.comments_to_end(0);
+    }
+
+    fn constant(&mut self, c: &Const) {
+        let typ = match c.typ {
+            ExprType::Int => "int",
+            ExprType::Float => "float",
+            _ => "string",
+        };
+        let value = self.expr(&c.value);
+        self.line(&format!("const {typ} {} = {value};", c.name.val()));
+    }
+
+    fn enumeration(&mut self, e: &Enum) {
+        if e.variants.is_empty() {
+            self.line(&format!("enum {} {{}}", e.name.val()));
+            return;
+        }
+        self.line(&format!("enum {} {{", e.name.val()));
+        self.indent += 1;
+        for (v, value) in &e.variants {
+            self.comments_before(v.loc(), self.indent);
+            self.source_line(v.loc().line);
+            match value {
+                Some(x) => {
+                    let x = self.expr(x);
+                    self.line(&format!("{} = {x},", v.val()));
+                }
+                None => self.line(&format!("{},", v.val())),
+            }
+        }
+        self.comments_to_end(self.indent);
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    pub fn sub(&mut self, sub: &Sub) {
+        let params: Vec<_> = sub
+            .params
+            .iter()
+            .map(|p| match p {
+                Param::Int(n) => format!("int {}", n.val()),
+                Param::Float(n) => format!("float {}", n.val()),
+            })
+            .collect();
+        self.line(&format!("sub {}({}) {{", sub.name.val(), params.join(", ")));
+        self.bloc_content(&sub.instructions);
+        self.line("}");
+    }
+
+    fn bloc_content(&mut self, instrs: &[Instr]) {
+        let outer = self.limit.clone();
+        // each instruction is followed by the next one written
+        let mut limits = Vec::with_capacity(instrs.len());
+        let mut next = outer.clone();
+        for i in instrs.iter().rev() {
+            limits.push(next.clone());
+            next = start(i).or(next);
+        }
+        self.indent += 1;
+        for (i, limit) in instrs.iter().zip(limits.into_iter().rev()) {
+            self.limit = limit;
+            self.instr(i);
+        }
+        self.limit = outer;
+        self.comments_to_end(self.indent);
+        self.indent -= 1;
+    }
+
+    /// Prints `instrs` as a block followed by what starts at `next`.
+    fn bloc_before(&mut self, instrs: &[Instr], next: Option<Location>) {
+        let outer = self.limit.clone();
+        self.limit = next.or(outer.clone());
+        self.bloc_content(instrs);
+        self.limit = outer;
+    }
+
+    fn bloc(&mut self, header: &str, instrs: &[Instr], footer: &str) {
+        self.line(&format!("{header}{{"));
+        self.bloc_content(instrs);
+        self.line(&format!("}}{footer}"));
+    }
+
+    fn if_chain(&mut self, header: &str, c: &Expr, i: &[Instr], e: &[Instr]) {
+        let c = self.expr(c);
+        self.line(&format!("{header}if ({c}) {{"));
+        self.bloc_before(i, e.iter().find_map(start));
+        match e {
+            [] => self.line("}"),
+            [Instr::If(c2, i2, e2)] => self.if_chain("} else ", c2, i2, e2),
+            _ => {
+                self.line("} else {");
+                self.bloc_content(e);
+                self.line("}");
+            }
+        }
+    }
+
+    fn call(&mut self, name: &Located<String>, a: &[Expr]) {
+        // a call written as such, like `ins_11("name")`, stays one
+        if self.layout.written(name.loc()) == Some(name.val().as_str()) {
+            let a = self.args(a);
+            return self.line(&format!("{}({a});", name.val()));
+        }
+        match (name.val().as_str(), a) {
+            ("ins_1", []) => self.line("delete;"),
+            ("ins_10", []) => self.line("return;"),
+            ("ins_11", [Expr::Str(s), rest @ ..]) if is_ident(s.val()) => {
+                let rest = self.args(rest);
+                self.line(&format!("@{}({rest});", s.val()))
+            }
+            ("ins_15", [Expr::Str(s), rest @ ..]) if is_ident(s.val()) => {
+                let rest = self.args(rest);
+                self.line(&format!("@{}({rest}) async;", s.val()))
+            }
+            ("ins_16", [Expr::Str(s), Expr::Int(n), rest @ ..])
+                if is_ident(s.val()) && *n.val() >= 0 =>
+            {
+                let rest = self.args(rest);
+                self.line(&format!("@{}({rest}) async({});", s.val(), n.val()))
+            }
+            ("ins_12", [Expr::Id(l), Expr::Float(t)])
+                if t.val().fract() == 0.0 && *t.val() >= 0.0 =>
+            {
+                self.line(&format!("goto {} @ {};", l.val(), *t.val() as i32))
+            }
+            (name, a) => {
+                let a = self.args(a);
+                self.line(&format!("{name}({a});"))
+            }
+        }
+    }
+
+    pub fn instr(&mut self, i: &Instr) {
+        if let Some(loc) = start(i) {
+            self.comments_before(&loc, self.indent);
+            self.source_line(loc.line);
+        }
+        match i {
+            Instr::Label(l) => self.outdented_line(&format!("{}:", l.val())),
+            Instr::TimeLabel(t, k) => self.outdented_line(&match k {
+                TimeLabelKind::Set => format!("{}:", t.val()),
+                TimeLabelKind::Add => format!("+{}:", t.val()),
+                TimeLabelKind::Sub => format!("-{}:", t.val()),
+            }),
+            Instr::RankLabel(r) => self.outdented_line(&rank_label(*r.val())),
+            Instr::Call(n, a) => self.call(n, a),
+            Instr::Bloc(b) => self.bloc("", b, ""),
+            Instr::PushExpr(e) => self.line(&format!("push({});", self.expr(e))),
+            Instr::If(c, i, e) => self.if_chain("", c, i, e),
+            Instr::Loop(b) => self.bloc("loop ", b, ""),
+            Instr::While(c, b) => self.bloc(&format!("while ({}) ", self.expr(c)), b, ""),
+            Instr::DoWhile(c, b) => self.bloc("do ", b, &format!(" while ({});", self.expr(c))),
+            Instr::Affect(n, e) => self.line(&format!("{} = {};", n.val(), self.expr(e))),
+            Instr::VarInt(n, e) => match e {
+                Some(e) => self.line(&format!("int {} = {};", n.val(), self.expr(e))),
+                None => self.line(&format!("int {};", n.val())),
+            },
+            Instr::VarFloat(n, e) => match e {
+                Some(e) => self.line(&format!("float {} = {};", n.val(), self.expr(e))),
+                None => self.line(&format!("float {};", n.val())),
+            },
+            Instr::Break => self.line("break;"),
+            Instr::Continue => self.line("continue;"),
+        }
+    }
+}
+
+pub fn print_ecl(ecl: &Ecl) -> String {
+    let mut p = Printer::new();
+    p.ecl(ecl);
+    p.finish()
+}
+
+/// `ecl` as formatted source, with the comments and the empty lines of
+/// `layout`.
+pub fn format_ecl(ecl: &Ecl, layout: Layout) -> String {
+    let mut p = Printer::with_layout(layout);
+    p.ecl(ecl);
+    p.finish()
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use crate::{
-    ecl_instructions::{MatchInsResult, MatchType},
-    error::{report_error_ext_one_more, report_note_simple, Error},
+    ecl_instructions::{InstructionSet, MatchInsResult, MatchType},
+    error::{emit, Diagnostic, Error, ErrorCode, Errors, Severity},
+    scpt::Encoding,
+    suggest,
 };
 
 use super::*;
@@ -15,7 +19,7 @@ pub enum Param {
 
 #[derive(Debug, Clone)]
 pub struct Sub {
-    pub name: String,
+    pub name: Located<String>,
     pub params: Vec<Param>,
     pub instructions: Vec<Instr>,
 }
@@ -31,6 +35,7 @@ impl Sub {
         }
 
         self.instructions = variables::replace_in_bloc(&mut scope, &self.instructions)?;
+        scope.warn_unused();
 
         self.instructions.insert(
             0,
@@ -43,7 +48,7 @@ impl Sub {
     }
 
     pub fn gen_label(&self, lbl_seed: &mut usize) -> String {
-        let mut n = self.name.clone();
+        let mut n = self.name.val().clone();
         n.push_str(&format!("_label_{}", lbl_seed));
         *lbl_seed += 1;
         n
@@ -61,96 +66,24 @@ impl Sub {
         Ok(())
     }
 
-    fn check_expressions(&mut self) -> Result<(), Error> {
+    /// Checks the instructions one by one, going on to the next one after an error.
+    /// `subs` and `known` are the names of the subs and the identifiers, for
+    /// the suggestions.
+    fn check_expressions(
+        &mut self,
+        instructions: &InstructionSet,
+        subs: &[String],
+        known: &[&str],
+    ) -> Result<(), Error> {
+        let mut errors = Errors::default();
         let mut new_instructions = Vec::new();
         for i in &self.instructions {
-            match i {
-                Instr::PushExpr(e) => {
-                    let mut e = e.clone();
-                    e.anotate()?;
-                    e.constant_fold();
-                    new_instructions.push(Instr::PushExpr(e));
-                }
-                Instr::Call(name, v) => {
-                    let mut args = Vec::new();
-                    let mut stoff = -1;
-                    for e in v {
-                        let mut e = e.clone();
-                        e.anotate()?;
-                        e.constant_fold();
-                        if e.is_primitive() {
-                            args.push(e);
-                        } else {
-                            let t = e.get_type()?;
-                            new_instructions.push(Instr::PushExpr(e));
-                            match t {
-                                ExprType::Int => args.push(Expr::VarInt(stoff.into())),
-                                ExprType::Float => args.push(Expr::VarFloat((stoff as f32).into())),
-                                ExprType::String => {
-                                    return Err(Error::Simple(
-                                        "Can't push string onto the stack".to_owned(),
-                                    ))
-                                }
-                                ExprType::Vararg => {
-                                    return Err(Error::BackEnd(
-                                        "should not have vararg at this point".to_owned(),
-                                    ))
-                                }
-                            }
-                            stoff -= 1;
-                        }
-                    }
-                    let ins_found = crate::ecl_instructions::matching_ins_sep(name.val(), &args)?;
-                    let ins_opcode = match ins_found {
-                        MatchInsResult::NoMatch(near_matches) => {
-                            report_error_ext_one_more(
-                                name.loc(),
-                                &format!("instruction `{}` does not exist", i.signature()?),
-                                "unknown instruction",
-                            );
-                            for nm in near_matches {
-                                match nm.mt {
-                                    MatchType::StringInVarargs => report_note_simple(&format!(
-                                        "found instruction {} but string was used in vararg",
-                                        nm.id.signature()
-                                    )),
-                                    MatchType::NameAndArgCountMatch => {
-                                        report_note_simple(&format!(
-                                        "found instruction {} with same name and number of arguments",
-                                        nm.id.signature()))
-                                    }
-                                    MatchType::NameMatch => {
-                                        report_note_simple(&format!(
-                                            "found instruction {} with same name",
-                                            nm.id.signature())
-                                        )
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            println!();
-                            return Err(Error::Simple(
-                                "Couldn't resolve instruction call".to_owned(),
-                            ));
-                        }
-                        MatchInsResult::MatchVA(oc, va) => {
-                            let mut new_args = args[..va].to_vec();
-                            new_args.push(Expr::Vararg(args[va..].to_vec()));
-                            args = new_args;
-                            oc
-                        }
-                        MatchInsResult::Match(oc) => oc,
-                    };
-
-                    let new_name = format!("ins_{ins_opcode}");
-                    // if vararg, insert type markers
-                    new_instructions.push(Instr::Call(new_name.into(), args));
-                }
-                _ => new_instructions.push(i.clone()),
+            if let Some(instrs) = errors.check(|| check_instr(instructions, i, subs, known)) {
+                new_instructions.extend(instrs);
             }
         }
         self.instructions = new_instructions;
-        Ok(())
+        errors.finish(())
     }
 
     fn check_if_sub_returns(&mut self) {
@@ -175,25 +108,116 @@ impl Sub {
         }
     }
 
-    pub fn process(&mut self) -> Result<(), Error> {
+    /// Names of the parameters and of the variables declared in the sub.
+    fn variable_names(&self) -> Vec<String> {
+        fn declared(instrs: &[Instr], names: &mut Vec<String>) {
+            for i in instrs {
+                if let Instr::VarInt(v, _) | Instr::VarFloat(v, _) = i {
+                    names.push(v.val().clone());
+                }
+                for b in i.blocks() {
+                    declared(b, names);
+                }
+            }
+        }
+        let mut names: Vec<_> = self
+            .params
+            .iter()
+            .map(|p| match p {
+                Param::Int(n) | Param::Float(n) => n.val().clone(),
+            })
+            .collect();
+        declared(&self.instructions, &mut names);
+        names
+    }
+
+    /// `subs` holds the names of the subs of the script.
+    pub fn process(
+        &mut self,
+        instructions: &InstructionSet,
+        encoding: Encoding,
+        idents: &HashMap<String, Expr>,
+        subs: &[String],
+    ) -> Result<(), Error> {
         let mut lbl_seed = 0usize;
+        let vars = self.variable_names();
+        let mut known: Vec<&str> = idents.keys().map(String::as_str).collect();
+        known.extend(vars.iter().map(String::as_str));
+        lints::unreachable_code(&self.instructions);
+        lints::backwards_time_labels(&self.instructions, &mut Some(0));
         self.replace_vars()?;
-        self.instructions = builtin_idents::replace(&self.instructions)?;
+        self.instructions = builtin_idents::replace(&self.instructions, idents)?;
+        lints::constant_conditions(&self.instructions);
         self.instructions = if_construct::desugar_bloc(self, &self.instructions, &mut lbl_seed)?;
         self.instructions = loop_construct::desugar_bloc(self, &self.instructions, &mut lbl_seed);
         self.instructions = while_construct::desugar_bloc(self, &self.instructions, &mut lbl_seed)?;
         // desugar other
         // maybe resolve variables before flattening anything.
-        self.check_expressions()?;
+        let mut errors = Errors::default();
+        errors.check(|| self.check_expressions(instructions, subs, &known));
+        errors.check(|| self.check_strings(encoding));
+        errors.check(|| self.check_times());
+        errors.finish(())?;
         self.check_if_sub_returns();
         self.resolve_push_expr()?;
-        self.resolve_labels();
+        self.resolve_labels(encoding, &known)?;
         // optimize jump chain and remove dead code at some point
-        // resolve other identifiers: vars, constants ... (right now there is none)
         Ok(())
     }
 
-    fn resolve_labels(&mut self) {
+    /// Checks that the string arguments can be written with `encoding`.
+    fn check_strings(&self, encoding: Encoding) -> Result<(), Error> {
+        let mut errors = Errors::default();
+        for i in &self.instructions {
+            if let Instr::Call(_, args) = i {
+                for a in args {
+                    if let Expr::Str(s) = a {
+                        errors.check(|| s.encode(encoding));
+                    }
+                }
+            }
+        }
+        errors.finish(())
+    }
+
+    /// Checks that the time labels don't take the time before 0, the time of
+    /// the instructions being unsigned. The time goes on in the order of the
+    /// instructions, as in the binary.
+    fn check_times(&self) -> Result<(), Error> {
+        fn check(instrs: &[Instr], time: &mut i64, errors: &mut Errors) {
+            for i in instrs {
+                let Instr::TimeLabel(t, k) = i else {
+                    for b in i.blocks() {
+                        check(b, time, errors);
+                    }
+                    continue;
+                };
+                let val = i64::from(*t.val());
+                *time = match k {
+                    TimeLabelKind::Set => val,
+                    TimeLabelKind::Add => *time + val,
+                    TimeLabelKind::Sub => *time - val,
+                };
+                if *time < 0 {
+                    emit(Diagnostic::error(
+                        ErrorCode::NegativeTime,
+                        t.loc(),
+                        &format!("time label `-{}:` goes before time 0", t.val()),
+                        &format!("at time {time}"),
+                    ));
+                    errors.fail();
+                    *time = 0;
+                }
+            }
+        }
+        let mut errors = Errors::default();
+        check(&self.instructions, &mut 0, &mut errors);
+        errors.finish(())
+    }
+
+    /// Replaces the labels by their offsets, any identifier left is unresolved.
+    fn resolve_labels(&mut self, encoding: Encoding, known: &[&str]) -> Result<(), Error> {
+        let mut errors = Errors::default();
         let mut labels: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
         let mut new_instructions = vec![];
         let mut pos = 0;
@@ -204,7 +228,7 @@ impl Sub {
                 }
                 _ => new_instructions.push(i.clone()),
             }
-            pos += i.size();
+            pos += i.size(encoding);
         }
         pos = 0;
         for ni in &mut new_instructions {
@@ -215,12 +239,171 @@ impl Sub {
                     .collect();
                 for e in v.iter_mut() {
                     e.replace_all_id(&map);
+                    if let Expr::Id(id) = e {
+                        let labels = labels.keys().map(String::as_str);
+                        unresolved_id(id, known.iter().copied().chain(labels));
+                        errors.fail();
+                    }
                 }
             }
-            pos += ni.size();
+            pos += ni.size(encoding);
         }
         self.instructions = new_instructions;
+        errors.finish(())
+    }
+}
+
+/// Reports `id`, which is none of the names `known`.
+fn unresolved_id<'a>(id: &Located<String>, known: impl IntoIterator<Item = &'a str>) {
+    let d = Diagnostic::error(
+        ErrorCode::UnresolvedIdentifier,
+        id.loc(),
+        &format!("unresolved identifier `{}`", id.val()),
+        "unresolved identifier",
+    );
+    let names: Vec<_> = suggest::closest(id.val(), known)
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    emit(suggest::did_you_mean(d, &names));
+}
+
+/// Reports the identifiers of `e`, an expression computed on the stack,
+/// where only the variables and the values can be.
+fn check_ids(e: &Expr, known: &[&str]) -> Result<(), Error> {
+    let mut ids = Vec::new();
+    e.clone().visit_mut(&mut |x| {
+        if let Expr::Id(id) = x {
+            ids.push(id.clone());
+        }
+    });
+    for id in &ids {
+        unresolved_id(id, known.iter().copied());
+    }
+    if !ids.is_empty() {
+        return Err(Error::Simple("unresolved identifier".to_owned()));
+    }
+    Ok(())
+}
+
+/// Types and folds the expressions of `i`, and resolves it among
+/// `instructions` if it is an instruction call. `subs` and `known` are the
+/// names suggested for an unknown instruction or identifier.
+fn check_instr(
+    instructions: &InstructionSet,
+    i: &Instr,
+    subs: &[String],
+    known: &[&str],
+) -> Result<Vec<Instr>, Error> {
+    let mut new_instructions = Vec::new();
+    match i {
+        Instr::PushExpr(e) => {
+            check_ids(e, known)?;
+            let mut e = e.clone();
+            e.anotate()?;
+            e.constant_fold();
+            new_instructions.push(Instr::PushExpr(e));
+        }
+        Instr::Call(name, v) => {
+            let mut args = Vec::new();
+            let mut stoff = -1;
+            for e in v {
+                let mut e = e.clone();
+                e.anotate()?;
+                e.constant_fold();
+                if e.is_primitive() {
+                    args.push(e);
+                } else {
+                    check_ids(&e, known)?;
+                    let t = e.get_type()?;
+                    let loc = e.loc();
+                    new_instructions.push(Instr::PushExpr(e));
+                    match t {
+                        ExprType::Int => args.push(Expr::VarInt(stoff.into())),
+                        ExprType::Float => args.push(Expr::VarFloat((stoff as f32).into())),
+                        ExprType::String => {
+                            return Err(Error::Type(
+                                "Can't push string onto the stack".to_owned(),
+                                loc,
+                            ))
+                        }
+                        ExprType::Vararg => {
+                            return Err(Error::BackEnd(
+                                "should not have vararg at this point".to_owned(),
+                            ))
+                        }
+                    }
+                    stoff -= 1;
+                }
+            }
+            let ins_found = instructions.matching_ins_sep(name.val(), &args)?;
+            let ins_opcode = match ins_found {
+                MatchInsResult::NoMatch(near_matches) => {
+                    // the arguments checked, typed where the call has expressions
+                    let call = Instr::Call(name.clone(), args.clone());
+                    let mut d = Diagnostic::error(
+                        ErrorCode::UnknownInstruction,
+                        name.loc(),
+                        &format!("instruction `{}` does not exist", call.signature()?),
+                        "unknown instruction",
+                    );
+                    for nm in near_matches {
+                        let note = match nm.mt {
+                            MatchType::StringInVarargs => format!(
+                                "found instruction {} but string was used in vararg",
+                                nm.id.signature()
+                            ),
+                            MatchType::NameAndArgCountMatch => format!(
+                                "found instruction {} with same name and number of arguments",
+                                nm.id.signature()
+                            ),
+                            MatchType::NameMatch => {
+                                format!("found instruction {} with same name", nm.id.signature())
+                            }
+                            _ => continue,
+                        };
+                        d.suggestions.push(nm.id.signature());
+                        d = d.with_note(Diagnostic::simple(Severity::Note, &note));
+                    }
+                    if d.notes.is_empty() {
+                        // no instruction has this name: a typo, or a sub called
+                        // without `@`
+                        let name = name.val();
+                        let mut names: Vec<_> =
+                            suggest::closest(name, instructions.instruction_names())
+                                .into_iter()
+                                .map(str::to_owned)
+                                .collect();
+                        let subs = subs.iter().map(String::as_str);
+                        let called = subs.clone().filter(|s| *s == name);
+                        names.extend(
+                            called
+                                .chain(suggest::closest(name, subs))
+                                .map(|s| format!("@{s}")),
+                        );
+                        d = suggest::did_you_mean(d, &names);
+                    }
+                    emit(d);
+                    return Err(Error::Simple(
+                        "Couldn't resolve instruction call".to_owned(),
+                    ));
+                }
+                MatchInsResult::MatchVA(oc, va) => {
+                    let mut new_args = args[..va].to_vec();
+                    new_args.push(Expr::Vararg(args[va..].to_vec()));
+                    args = new_args;
+                    oc
+                }
+                MatchInsResult::Match(oc) => oc,
+            };
+
+            let new_name = format!("ins_{ins_opcode}");
+            // if vararg, insert type markers
+            new_instructions.push(Instr::Call(new_name.into(), args));
+        }
+        _ => new_instructions.push(i.clone()),
     }
+    Ok(new_instructions)
 }
 
 fn resolve_param(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
@@ -252,7 +435,7 @@ fn resolve_sub(typ: &[String], args: &[AstNode]) -> Result<AstNode, Error> {
     if args.len() != 3 {
         return Err(Error::Grammar("Sub command takes 3 parameters".to_owned()));
     }
-    let val = args[0].clone().token().id();
+    let val = args[0].clone().token().id_loc();
     let param_list = args[1].clone().list();
     let ins_list = args[2].clone().list();
     Ok(AstNode::Sub(Sub {
@@ -8,22 +8,23 @@ pub enum Token {
     Int(i32, Location),
     Float(f32, Location),
     Id(String, Location),
+    #[allow(dead_code)]
     Other(String, Location),
 }
 
-impl From<&crate::lexer::Token<&str>> for Token {
-    fn from(value: &crate::lexer::Token<&str>) -> Self {
-        match value.kind {
-            "id" => Self::Id(value.text.to_string(), value.loc.clone()),
-            "int" => Self::Int(tokens_to_vals::int(&value.text), value.loc.clone()),
-            "float" => Self::Float(tokens_to_vals::float(&value.text), value.loc.clone()),
-            "str" => Self::Strn(tokens_to_vals::string(&value.text), value.loc.clone()),
-            _ => Self::Other(value.kind.to_string(), value.loc.clone()),
-        }
+impl Token {
+    /// Converts a lexer token, decoding the literals.
+    pub fn new(value: &crate::lexer::Token<&str>) -> Result<Self, Error> {
+        let loc = value.loc.clone();
+        Ok(match value.kind {
+            "id" => Self::Id(value.text.to_string(), loc),
+            "int" => Self::Int(tokens_to_vals::int(&value.text, &loc)?, loc),
+            "float" => Self::Float(tokens_to_vals::float(&value.text), loc),
+            "str" => Self::Strn(tokens_to_vals::string(&value.text, &loc)?, loc),
+            _ => Self::Other(value.kind.to_string(), loc),
+        })
     }
-}
 
-impl Token {
     pub fn loc(&self) -> &Location {
         match self {
             Self::Strn(_, l) => l,
@@ -34,14 +35,6 @@ impl Token {
         }
     }
 
-    pub fn strn(&self) -> String {
-        if let Self::Strn(s, _) = self {
-            s.clone()
-        } else {
-            panic!("Token is not variant Strn");
-        }
-    }
-
     pub fn strn_loc(&self) -> Located<String> {
         if let Self::Strn(s, l) = self {
             Located::new(s.clone(), l.clone())
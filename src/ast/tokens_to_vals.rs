@@ -1,27 +1,173 @@
-pub fn string(s: &str) -> String {
-    // remove "" and do escape proceed to escape.
-    let mut s = s
-        .strip_prefix('"')
-        .unwrap()
-        .strip_suffix('"')
-        .unwrap()
-        .to_string();
-    s = s.replace("\\n", "\n");
-    s = s.replace("\\t", "\t");
-    s = s.replace("\\\"", "\"");
-    s = s.replace("\\'", "'");
-    // TODO: all escape sequences
-    s.to_string()
+use crate::{
+    error::{report_error_ext, warn, Error, ErrorCode},
+    lexer::Location,
+    lint::Lint,
+};
+
+/// Reports a bad escape sequence at `start..end` of the literal located at `loc`.
+fn escape_error(loc: &Location, start: usize, end: usize, msg: &str) -> Error {
+    let loc = Location {
+        file: loc.file,
+        line: loc.line,
+        span: loc.span.start + start..loc.span.start + end,
+    };
+    report_error_ext(
+        ErrorCode::InvalidEscape,
+        &loc,
+        msg,
+        "invalid escape sequence",
+    );
+    Error::Simple("invalid escape sequence".to_owned())
+}
+
+/// Value of `lit`, a string or character literal with its quotes, located at `loc`.
+fn unescape(lit: &str, loc: &Location) -> Result<String, Error> {
+    let body = &lit[1..lit.len() - 1];
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        // offsets in the literal, quote included
+        let start = i + 1;
+        let Some((_, e)) = chars.next() else {
+            return Err(escape_error(
+                loc,
+                start,
+                start + 1,
+                "unterminated escape sequence",
+            ));
+        };
+        let decoded = match e {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'x' => {
+                let digits: String = (0..2)
+                    .map_while(|_| chars.next_if(|(_, c)| c.is_ascii_hexdigit()))
+                    .map(|(_, c)| c)
+                    .collect();
+                let end = start + 2 + digits.len();
+                if digits.len() != 2 {
+                    return Err(escape_error(
+                        loc,
+                        start,
+                        end,
+                        "`\\x` takes exactly 2 hex digits",
+                    ));
+                }
+                let v = u8::from_str_radix(&digits, 16).unwrap();
+                if v > 0x7f {
+                    return Err(escape_error(
+                        loc,
+                        start,
+                        end,
+                        "`\\x` escapes go up to `\\x7f`, use `\\u{...}` for other characters",
+                    ));
+                }
+                v as char
+            }
+            'u' => {
+                let mut end = start + 2;
+                if chars.next_if(|(_, c)| *c == '{').is_none() {
+                    return Err(escape_error(loc, start, end, "expected `{` after `\\u`"));
+                }
+                let mut digits = String::new();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                }
+                end += 1 + digits.len();
+                if chars.next_if(|(_, c)| *c == '}').is_none() {
+                    return Err(escape_error(
+                        loc,
+                        start,
+                        end,
+                        "unterminated `\\u{...}` escape",
+                    ));
+                }
+                end += 1;
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| digits.len() <= 6)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| escape_error(loc, start, end, "invalid unicode character"))?
+            }
+            e => {
+                return Err(escape_error(
+                    loc,
+                    start,
+                    start + 1 + e.len_utf8(),
+                    &format!("unknown escape sequence `\\{e}`"),
+                ))
+            }
+        };
+        out.push(decoded);
+    }
+    Ok(out)
 }
 
-pub fn int(s: &str) -> i32 {
+/// Value of a string literal, quotes included.
+pub fn string(s: &str, loc: &Location) -> Result<String, Error> {
+    unescape(s, loc)
+}
+
+/// Value of an int literal: a number, `true`, `false` or a character.
+pub fn int(s: &str, loc: &Location) -> Result<i32, Error> {
     if s == "true" {
-        return 1;
+        return Ok(1);
     } else if s == "false" {
-        return 0;
+        return Ok(0);
+    } else if s.starts_with('\'') {
+        let c = unescape(s, loc)?;
+        let mut chars = c.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c as i32),
+            _ => {
+                report_error_ext(
+                    ErrorCode::CharLiteral,
+                    loc,
+                    "character literals hold one character",
+                    "not a character",
+                );
+                Err(Error::Simple("invalid character literal".to_owned()))
+            }
+        };
     }
-    // TODO: check all cases
-    s.parse().unwrap()
+    // the other bases give the bits of the int, like the colors 0xFF000000
+    let value = if let Some(hex) = s.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).map(|v| v as i32)
+    } else if let Some(bin) = s.strip_prefix("0b") {
+        u32::from_str_radix(bin, 2).map(|v| v as i32)
+    } else if s.len() > 1 && s.starts_with('0') {
+        let value = u32::from_str_radix(&s[1..], 8).map(|v| v as i32);
+        if let Ok(v) = value {
+            warn(
+                Lint::OctalLiterals,
+                loc,
+                &format!("`{s}` is an octal literal, worth {v}"),
+                &format!("write `{v}` or drop the leading zero"),
+            );
+        }
+        value
+    } else {
+        s.parse()
+    };
+    value.map_err(|_| {
+        let max = if s.starts_with('0') { "32 bits" } else { "an int" };
+        report_error_ext(
+            ErrorCode::IntLiteral,
+            loc,
+            &format!("integer literal `{s}` is out of range"),
+            &format!("doesn't fit in {max}"),
+        );
+        Error::Simple("invalid integer literal".to_owned())
+    })
 }
 
 pub fn float(s: &str) -> f32 {
@@ -1,9 +1,8 @@
 use crate::{
-    error::{
-        create_report_content, report_error_ext, report_error_ext_one_more, report_message_header,
-        ErrReport, Error,
-    },
+    error::{emit, warn, Diagnostic, Error, ErrorCode, Errors},
     lexer::Location,
+    lint::Lint,
+    suggest,
 };
 
 use super::*;
@@ -49,6 +48,8 @@ impl Variable {
 #[derive(Clone)]
 pub struct Scope {
     variables: Vec<Variable>,
+    /// Whether each variable is read somewhere.
+    used: Vec<bool>,
     parent_scope: Vec<Scope>,
     local_max_offset: i32,
     pub max_offset: i32,
@@ -58,6 +59,7 @@ impl Scope {
     pub fn new() -> Self {
         Self {
             variables: vec![],
+            used: vec![],
             parent_scope: vec![],
             local_max_offset: 0,
             max_offset: 0,
@@ -67,14 +69,18 @@ impl Scope {
     pub fn push_scope(&self) -> Self {
         Self {
             variables: vec![],
+            used: vec![],
             max_offset: self.max_offset,
             local_max_offset: self.local_max_offset,
             parent_scope: vec![self.clone()],
         }
     }
 
+    /// Returns the enclosing scope, warning about the variables of this one
+    /// that were never read.
     pub fn pop_scope(mut self) -> Result<Self, Error> {
         assert!(!self.parent_scope.is_empty());
+        self.warn_unused();
         let mut s = self
             .parent_scope
             .pop()
@@ -95,51 +101,67 @@ impl Scope {
             Variable::Float(o as f32, v.clone())
         };
         if let Some(other) = self.variables.iter().find(|va| va.name() == v.val()) {
-            report_error_ext_one_more(
-                v.loc(),
-                &format!("Variable `{}` already exists", v.val()),
-                "Variable already exists",
-            );
-            report_message_header(
-                other.loc(),
-                "Variable defined here:",
-                "note",
-                crossterm::style::Color::Blue,
-                false,
+            emit(
+                Diagnostic::error(
+                    ErrorCode::VariableExists,
+                    v.loc(),
+                    &format!("Variable `{}` already exists", v.val()),
+                    "Variable already exists",
+                )
+                .with_note(Diagnostic::note(other.loc(), "Variable defined here:")),
             );
-            println!(
-                "{}",
-                create_report_content(
-                    other.loc().line..other.loc().line + 1,
-                    vec![ErrReport {
-                        line: other.loc().line,
-                        span: other.loc().span.clone(),
-                        col: crossterm::style::Color::Blue,
-                        msg: "",
-                        underline: '~',
-                        col_text: true,
-                    }],
-                    false,
+            return Err(Error::Simple("Variable already exists".to_owned()));
+        }
+        if let Some(outer) = self.parent_scope.first().and_then(|p| p.get_var(v.val())) {
+            emit(
+                Diagnostic::warning(
+                    Lint::ShadowedVariables,
+                    v.loc(),
+                    &format!(
+                        "variable `{}` shadows a variable of an enclosing block",
+                        v.val()
+                    ),
+                    "shadows the outer variable",
                 )
+                .with_note(Diagnostic::note(outer.loc(), "Variable defined here:")),
             );
-            return Err(Error::Simple(format!("Variable already exists")));
         }
         self.variables.push(var);
+        self.used.push(false);
         Ok(())
     }
 
+    /// Warns about the variables of this scope never read, except the ones
+    /// whose name starts with `_`.
+    pub fn warn_unused(&self) {
+        for (v, used) in self.variables.iter().zip(&self.used) {
+            if !used && !v.name().starts_with('_') {
+                warn(
+                    Lint::UnusedVariables,
+                    v.loc(),
+                    &format!("unused variable `{}`", v.name()),
+                    "never read",
+                );
+            }
+        }
+    }
+
     pub fn assign(&self, v: &Located<String>, expr: &Expr) -> Result<Vec<Instr>, Error> {
         let var = match self.get_var(v.val()) {
             Some(v) => v,
             None => {
-                report_error_ext(
+                let d = Diagnostic::error(
+                    ErrorCode::UnknownVariable,
                     v.loc(),
                     &format!("Variable `{}` doesn't exist", v.val()),
                     "Variable doesn't exist",
                 );
-                return Err(Error::Simple(format!(
-                    "Variable doesn't exist for assignment"
-                )));
+                let names = suggest::closest(v.val(), self.names());
+                let names: Vec<_> = names.into_iter().map(str::to_owned).collect();
+                emit(suggest::did_you_mean(d, &names));
+                return Err(Error::Simple(
+                    "Variable doesn't exist for assignment".to_owned(),
+                ));
             }
         };
         Ok(vec![Instr::PushExpr(expr.clone()), var.pop_instr()])
@@ -157,9 +179,29 @@ impl Scope {
             Some(_) => found,
         }
     }
+
+    /// Names of the variables of this scope and of the enclosing ones.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.variables.iter().map(|v| v.name()).collect();
+        if let Some(p) = self.parent_scope.first() {
+            names.extend(p.names());
+        }
+        names
+    }
+
+    /// Like `get_var`, marking the variable as read.
+    pub fn use_var(&mut self, name: &str) -> Option<&Variable> {
+        match self.variables.iter().position(|v| v.name() == name) {
+            Some(i) => {
+                self.used[i] = true;
+                Some(&self.variables[i])
+            }
+            None => self.parent_scope.first_mut()?.use_var(name),
+        }
+    }
 }
 
-pub fn replace_in_expr(scope: &Scope, e: &mut Expr) {
+pub fn replace_in_expr(scope: &mut Scope, e: &mut Expr) {
     match e {
         Expr::VarInt(_) | Expr::VarFloat(_) | Expr::Int(_) | Expr::Float(_) | Expr::Str(_) => {}
         Expr::Vararg(ref mut va) => {
@@ -192,14 +234,18 @@ pub fn replace_in_expr(scope: &Scope, e: &mut Expr) {
         | Expr::Not(ref mut e, _)
         | Expr::Uminus(ref mut e, _) => replace_in_expr(scope, e),
         Expr::Id(s) => {
-            if let Some(v) = scope.get_var(s.val()) {
-                *e = v.expr();
+            if let Some(v) = scope.use_var(s.val()) {
+                // where the variable is read, for the errors on its type
+                *e = v.expr().relocate(s.loc());
             }
         }
     }
 }
 
+/// Replaces the variables of `ins`, going on after the errors of a
+/// declaration or an assignment.
 pub fn replace_in_bloc(scope: &mut Scope, ins: &Vec<Instr>) -> Result<Vec<Instr>, Error> {
+    let mut errors = Errors::default();
     let mut new_ins = Vec::new();
     for i in ins {
         match i {
@@ -223,13 +269,17 @@ pub fn replace_in_bloc(scope: &mut Scope, ins: &Vec<Instr>) -> Result<Vec<Instr>
             }
             Instr::Bloc(l) => {
                 let mut new_scope = scope.push_scope();
-                let new_l = replace_in_bloc(&mut new_scope, l)?;
+                let new_l = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 new_ins.extend(new_l);
             }
             Instr::Loop(l) => {
                 let mut new_scope = scope.push_scope();
-                let new_l = replace_in_bloc(&mut new_scope, l)?;
+                let new_l = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 new_ins.push(Instr::Loop(new_l));
             }
@@ -237,10 +287,14 @@ pub fn replace_in_bloc(scope: &mut Scope, ins: &Vec<Instr>) -> Result<Vec<Instr>
                 let mut new_e = e.clone();
                 replace_in_expr(scope, &mut new_e);
                 let mut new_scope = scope.push_scope();
-                let new_l1 = replace_in_bloc(&mut new_scope, l1)?;
+                let new_l1 = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l1))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 let mut new_scope = scope.push_scope();
-                let new_l2 = replace_in_bloc(&mut new_scope, l2)?;
+                let new_l2 = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l2))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 new_ins.push(Instr::If(new_e, new_l1, new_l2));
             }
@@ -248,7 +302,9 @@ pub fn replace_in_bloc(scope: &mut Scope, ins: &Vec<Instr>) -> Result<Vec<Instr>
                 let mut new_e = e.clone();
                 replace_in_expr(scope, &mut new_e);
                 let mut new_scope = scope.push_scope();
-                let new_l = replace_in_bloc(&mut new_scope, l)?;
+                let new_l = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 new_ins.push(Instr::While(new_e, new_l));
             }
@@ -256,38 +312,34 @@ pub fn replace_in_bloc(scope: &mut Scope, ins: &Vec<Instr>) -> Result<Vec<Instr>
                 let mut new_e = e.clone();
                 replace_in_expr(scope, &mut new_e);
                 let mut new_scope = scope.push_scope();
-                let new_l = replace_in_bloc(&mut new_scope, l)?;
+                let new_l = errors
+                    .check(|| replace_in_bloc(&mut new_scope, l))
+                    .unwrap_or_default();
                 *scope = new_scope.pop_scope()?;
                 new_ins.push(Instr::DoWhile(new_e, new_l));
             }
             Instr::Affect(v, e) => {
                 let mut new_e = e.clone();
                 replace_in_expr(scope, &mut new_e);
-                new_ins.extend(scope.assign(v, &new_e)?);
+                new_ins.extend(errors.check(|| scope.assign(v, &new_e)).unwrap_or_default());
             }
             Instr::VarInt(v, e_opt) => {
-                scope.add_var(v, 1)?;
-                match e_opt {
-                    Some(e) => {
-                        let mut new_e = e.clone();
-                        replace_in_expr(scope, &mut new_e);
-                        new_ins.extend(scope.assign(v, &new_e)?);
-                    }
-                    None => {}
+                errors.check(|| scope.add_var(v, 1));
+                if let Some(e) = e_opt {
+                    let mut new_e = e.clone();
+                    replace_in_expr(scope, &mut new_e);
+                    new_ins.extend(errors.check(|| scope.assign(v, &new_e)).unwrap_or_default());
                 }
             }
             Instr::VarFloat(v, e_opt) => {
-                scope.add_var(v, 2)?;
-                match e_opt {
-                    Some(e) => {
-                        let mut new_e = e.clone();
-                        replace_in_expr(scope, &mut new_e);
-                        new_ins.extend(scope.assign(v, &new_e)?);
-                    }
-                    None => {}
+                errors.check(|| scope.add_var(v, 2));
+                if let Some(e) = e_opt {
+                    let mut new_e = e.clone();
+                    replace_in_expr(scope, &mut new_e);
+                    new_ins.extend(errors.check(|| scope.assign(v, &new_e)).unwrap_or_default());
                 }
             }
         }
     }
-    Ok(new_ins)
+    errors.finish(new_ins)
 }
@@ -1,4 +1,4 @@
-use crate::error::{report_error_ext, Error};
+use crate::error::{report_error_ext, Error, ErrorCode};
 
 use super::*;
 
@@ -30,6 +30,7 @@ pub fn desugar(
     e.constant_fold();
     if e.get_type()? != ExprType::Int {
         report_error_ext(
+            ErrorCode::NonIntCondition,
             &e.loc(),
             "Condition expression should be of type int",
             "This expression should have type int",
@@ -38,9 +39,9 @@ pub fn desugar(
             "Condition for while should be type Int".to_owned(),
         ));
     }
-    if e.is_primitive() && !e.is_var() {
-        let i = e.int().into_val();
-        if i != 0 {
+    // an identifier left is unresolved, reported with the pushed expression
+    if let Expr::Int(i) = &e {
+        if *i.val() != 0 {
             return Ok(super::loop_construct::desugar(sub, bloc, lbl_seed));
         } else if first_jump {
             return Ok(vec![]);
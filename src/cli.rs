@@ -0,0 +1,300 @@
+use eclc::{
+    error::Error,
+    lint::{Level, Lint, Lints},
+    scpt::Encoding,
+};
+
+pub const USAGE: &str = "\
+Usage: eclc [COMMAND] [OPTIONS] <INPUT>...
+
+Compiles eclc source files to ECL binaries.
+
+Commands:
+  build                   Compile source files (default)
+  decompile               Print ECL binaries back as eclc source
+  verify                  Check that compiling, decompiling and compiling again
+                          gives the same binary
+  fmt                     Rewrite source files in the canonical format
+  lsp                     Run a language server on stdin and stdout, for the
+                          editors. Takes no input
+
+Arguments:
+  <INPUT>...              Files to process, `-` for the standard input
+
+Options:
+  -o, --output <FILE>     Write the output to <FILE> (only with a single input).
+                          With verify, the decompiled source is written there
+      --check             With fmt, write nothing but fail if a file isn't
+                          formatted, for CI
+      --grammar <FILE>    Use the language grammar <FILE> instead of the built-in
+                          one, to try changes to the language
+      --map <FILE>        Load instruction names and signatures from the eclmap
+                          <FILE>, on top of the built-in ones
+      --encoding <ENC>    Encoding of the strings in the binaries: utf8 or sjis
+                          [default: sjis]
+      --emit <KIND>       Output kind: bin, ast or hex [default: bin]
+      --message-format <FMT>
+                          How the errors and warnings are printed: human, or
+                          json for one JSON object per line, without the status
+                          messages [default: human]
+  -j, --jobs <N>          Number of files processed at the same time
+                          [default: number of CPUs]
+  -W, --warn <LINT>       Report <LINT> as a warning
+  -A, --allow <LINT>      Don't report <LINT>
+  -D, --deny <LINT>       Report <LINT> as an error, which fails the compilation.
+                          <LINT> is one of the lints below, or `warnings` for
+                          all of them. The last option given for a lint wins
+      -Werror             Same as `-D warnings`, to make CI builds fail on warnings
+      --explain <CODE>    Explain the error <CODE>, like E0007, with an example
+  -q, --quiet             Only print errors
+  -v, --verbose           Print every compilation step
+  -h, --help              Print this help
+  -V, --version           Print version
+
+Lints (all warnings by default):
+  unused_variables        Variable declared but never read
+  unused_subs             Sub never called, in a script without `ecli`
+  unreachable_code        Code after a `return` or a `delete`
+  shadowed_variables      Variable with the name of one of an enclosing block
+  constant_conditions     `if` or `while` whose condition is always the same
+  backwards_time_labels   Time label before the time already reached
+  moved_comments          Comment inside a statement, moved after it by fmt
+  octal_literals          Int literal with a leading zero, read in octal";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// ECL binary, written to the output file.
+    Bin,
+    /// Processed AST, printed as debug output.
+    Ast,
+    /// Hex dump of the ECL binary.
+    Hex,
+}
+
+impl Emit {
+    fn from_arg(s: &str) -> Result<Self, Error> {
+        match s {
+            "bin" => Ok(Self::Bin),
+            "ast" => Ok(Self::Ast),
+            "hex" => Ok(Self::Hex),
+            _ => Err(Error::Simple(format!(
+                "unknown emit kind `{s}` (expected bin, ast or hex)"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Colored, with the source code pointed at.
+    Human,
+    /// One JSON object per line, for the editors and the CI.
+    Json,
+}
+
+impl MessageFormat {
+    fn from_arg(s: &str) -> Result<Self, Error> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::Simple(format!(
+                "unknown message format `{s}` (expected human or json)"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Build,
+    Decompile,
+    Verify,
+    Fmt,
+    Lsp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub command: Command,
+    pub inputs: Vec<String>,
+    pub output: Option<String>,
+    pub grammar: Option<String>,
+    pub map: Option<String>,
+    pub encoding: Encoding,
+    pub emit: Emit,
+    pub message_format: MessageFormat,
+    pub verbosity: Verbosity,
+    pub jobs: usize,
+    pub lints: Lints,
+    /// With fmt, report the files not formatted instead of formatting them.
+    pub check: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::Build,
+            inputs: vec![],
+            output: None,
+            grammar: None,
+            map: None,
+            encoding: Encoding::default(),
+            emit: Emit::Bin,
+            message_format: MessageFormat::Human,
+            verbosity: Verbosity::Normal,
+            jobs: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            lints: Lints::default(),
+            check: false,
+        }
+    }
+}
+
+impl Options {
+    /// Name of the file generated from `input`, or `None` if the output goes to stdout.
+    pub fn output_for(&self, input: &str) -> Option<String> {
+        if let Some(o) = &self.output {
+            return Some(o.clone());
+        }
+        if input == "-" {
+            return None;
+        }
+        if self.command == Command::Fmt {
+            return Some(input.to_owned());
+        }
+        if self.command != Command::Build || self.emit != Emit::Bin {
+            return None;
+        }
+        let path = std::path::Path::new(input);
+        Some(path.with_extension("ecl").to_string_lossy().into_owned())
+    }
+}
+
+pub enum Action {
+    Run(Options),
+    Help,
+    Version,
+    /// Explain an error code.
+    Explain(String),
+}
+
+/// Splits `--name=value` into its two parts.
+fn split_long(arg: &str) -> (&str, Option<&str>) {
+    match arg.split_once('=') {
+        Some((n, v)) if n.starts_with("--") => (n, Some(v)),
+        _ => (arg, None),
+    }
+}
+
+fn parse_jobs(s: &str) -> Result<usize, Error> {
+    match s.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(Error::Simple(format!(
+            "invalid number of jobs `{s}` (expected a positive integer)"
+        ))),
+    }
+}
+
+/// Sets the level of the lint `name`, or of every lint for `warnings`.
+fn set_lint(lints: &mut Lints, name: &str, level: Level) -> Result<(), Error> {
+    if name == "warnings" {
+        lints.set_all(level);
+        return Ok(());
+    }
+    let lint = Lint::from_name(name).ok_or_else(|| {
+        Error::Simple(format!(
+            "unknown lint `{name}` (expected warnings or one of the lints of `--help`)"
+        ))
+    })?;
+    lints.set(lint, level);
+    Ok(())
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Action, Error> {
+    let mut opts = Options::default();
+    let mut args = args.peekable();
+    let mut only_inputs = false;
+
+    match args.peek().map(|s| &s[..]) {
+        Some("build") => {
+            args.next();
+        }
+        Some("decompile") => {
+            opts.command = Command::Decompile;
+            args.next();
+        }
+        Some("verify") => {
+            opts.command = Command::Verify;
+            args.next();
+        }
+        Some("fmt") => {
+            opts.command = Command::Fmt;
+            args.next();
+        }
+        Some("lsp") => {
+            opts.command = Command::Lsp;
+            args.next();
+        }
+        _ => {}
+    }
+
+    while let Some(arg) = args.next() {
+        if only_inputs || !arg.starts_with('-') || arg == "-" {
+            opts.inputs.push(arg);
+            continue;
+        }
+        let (name, inline_val) = split_long(&arg);
+        let mut value = |name: &str| -> Result<String, Error> {
+            if let Some(v) = inline_val {
+                return Ok(v.to_owned());
+            }
+            args.next()
+                .ok_or(Error::Simple(format!("option `{name}` requires a value")))
+        };
+        match name {
+            "--" => only_inputs = true,
+            "-h" | "--help" => return Ok(Action::Help),
+            "-V" | "--version" => return Ok(Action::Version),
+            "--explain" => return Ok(Action::Explain(value(name)?)),
+            "-o" | "--output" => opts.output = Some(value(name)?),
+            "--check" => opts.check = true,
+            "--grammar" => opts.grammar = Some(value(name)?),
+            "--map" => opts.map = Some(value(name)?),
+            "--encoding" => opts.encoding = Encoding::from_arg(&value(name)?)?,
+            "--emit" => opts.emit = Emit::from_arg(&value(name)?)?,
+            "--message-format" => opts.message_format = MessageFormat::from_arg(&value(name)?)?,
+            "-j" | "--jobs" => opts.jobs = parse_jobs(&value(name)?)?,
+            "-W" | "--warn" => set_lint(&mut opts.lints, &value(name)?, Level::Warn)?,
+            "-A" | "--allow" => set_lint(&mut opts.lints, &value(name)?, Level::Allow)?,
+            "-D" | "--deny" => set_lint(&mut opts.lints, &value(name)?, Level::Deny)?,
+            "-Werror" => opts.lints.set_all(Level::Deny),
+            "-q" | "--quiet" => opts.verbosity = Verbosity::Quiet,
+            "-v" | "--verbose" => opts.verbosity = Verbosity::Verbose,
+            _ => return Err(Error::Simple(format!("unknown option `{arg}`"))),
+        }
+    }
+
+    if opts.command == Command::Lsp {
+        if !opts.inputs.is_empty() {
+            return Err(Error::Simple(
+                "`lsp` takes no input file, the editor sends them".to_owned(),
+            ));
+        }
+        return Ok(Action::Run(opts));
+    }
+    if opts.inputs.is_empty() {
+        return Err(Error::Simple(format!("no input file\n\n{USAGE}")));
+    }
+    if opts.output.is_some() && opts.inputs.len() > 1 {
+        return Err(Error::Simple(
+            "`--output` can't be used with multiple input files".to_owned(),
+        ));
+    }
+    Ok(Action::Run(opts))
+}
@@ -1,124 +1,45 @@
-use crate::ast::Ecl;
-
-#[repr(C)]
-struct EclCode {
-    magic: [u8; 4],
-    unknown1: u16,
-    include_length: u16,
-    include_offset: u32,
-    zero1: [u8; 4],
-    sub_count: u32,
-    zero2: [u8; 16],
-}
-
-impl EclCode {
-    fn new(include_length: u16, sub_count: u32) -> Self {
-        Self {
-            magic: [b'S', b'C', b'P', b'T'],
-            unknown1: 1u16,
-            include_length,
-            include_offset: 36,
-            zero1: [0, 0, 0, 0],
-            sub_count,
-            zero2: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        }
-    }
-
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut b = Vec::new();
-        b.extend_from_slice(&self.magic);
-        b.extend_from_slice(&self.unknown1.to_ne_bytes());
-        b.extend_from_slice(&self.include_length.to_ne_bytes());
-        b.extend_from_slice(&self.include_offset.to_ne_bytes());
-        b.extend_from_slice(&self.zero1);
-        b.extend_from_slice(&self.sub_count.to_ne_bytes());
-        b.extend_from_slice(&self.zero2);
-        b
-    }
-}
-
-#[repr(C)]
-struct IncListHeaderCode {
-    magic: [u8; 4],
-    count: u32,
-}
-
-impl IncListHeaderCode {
-    fn new(typ: &str, count: usize) -> Self {
-        let mut chrs = typ.chars();
-        let magic: [u8; 4] = [
-            chrs.next().unwrap() as u8,
-            chrs.next().unwrap() as u8,
-            chrs.next().unwrap() as u8,
-            chrs.next().unwrap() as u8,
-        ];
-        Self {
-            magic,
-            count: count as u32,
-        }
-    }
-
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut data = self.magic.to_vec();
-        data.extend_from_slice(&self.count.to_ne_bytes());
-        data
-    }
-}
-
-struct IncList<'a> {
-    header: IncListHeaderCode,
-    strings: &'a Vec<String>,
-}
-
-impl<'a> IncList<'a> {
-    fn new(typ: &str, strings: &'a Vec<String>) -> Self {
-        let header = IncListHeaderCode::new(typ, strings.len());
-        Self { header, strings }
-    }
-
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut b = self.header.to_bytes();
-        for s in self.strings {
-            b.extend(s.bytes());
-            b.push(0);
-        }
-        let length = b.len();
-        if length % 4 != 0 {
-            let padding = 4 - (length % 4);
-            b.extend(vec![0u8; padding]);
-        }
-        b
-    }
-}
-
-pub fn generate(ecl: &Ecl) -> Vec<u8> {
-    let anmi = IncList::new("ANIM", &ecl.anmi).to_bytes();
-    let ecli = IncList::new("ECLI", &ecl.ecli).to_bytes();
-    let include_size = anmi.len() + ecli.len();
-    let header = EclCode::new(include_size as u16, ecl.subs.len() as u32);
-    let mut bytes = header.to_bytes();
-    bytes.extend(anmi);
-    bytes.extend(ecli);
-
-    let mut sub_names = Vec::new();
+use crate::{
+    ast::Ecl,
+    scpt::{self, Encoding, Header, Writer},
+};
+
+pub fn generate(ecl: &Ecl, encoding: Encoding) -> Vec<u8> {
+    let mut includes = Writer::new();
+    for (magic, list) in [(scpt::ANIM_MAGIC, &ecl.anmi), (scpt::ECLI_MAGIC, &ecl.ecli)] {
+        let names: Vec<Vec<u8>> = list
+            .iter()
+            .map(|s| encoding.encode(s.val()).expect("checked by `Ecl::process`"))
+            .collect();
+        let names: Vec<&[u8]> = names.iter().map(|n| &n[..]).collect();
+        scpt::write_inc_list(&mut includes, magic, &names);
+    }
+    let mut w = Writer::new();
+    Header {
+        version: scpt::VERSION,
+        include_length: includes.pos() as u16,
+        include_offset: scpt::INCLUDE_OFFSET,
+        sub_count: ecl.subs.len() as u32,
+    }
+    .write(&mut w);
+    w.zeros(scpt::INCLUDE_OFFSET as usize - w.pos());
+    w.bytes(&includes.into_bytes());
+
+    let mut sub_names = Writer::new();
     for s in &ecl.subs {
-        sub_names.extend(s.name.bytes());
-        sub_names.push(0u8);
+        sub_names.cstr(s.name.val().as_bytes());
     }
-    sub_names.extend(vec![0u8; 4 - (sub_names.len() % 4)]);
+    sub_names.zeros(4 - sub_names.pos() % 4);
 
-    let mut sub_offset = bytes.len() + 4 * ecl.subs.len() + sub_names.len();
+    let mut sub_offset = w.pos() + 4 * ecl.subs.len() + sub_names.pos();
     let mut sub_data = Vec::new();
-    let mut sub_offsets = Vec::new();
     // offset table
     for s in &ecl.subs {
-        sub_offsets.extend_from_slice(&(sub_offset as u32).to_ne_bytes());
-        let sub_bytes = super::gen_sub(s);
+        w.u32(sub_offset as u32);
+        let sub_bytes = super::gen_sub(s, encoding);
         sub_offset += sub_bytes.len();
         sub_data.extend_from_slice(&sub_bytes);
     }
-    bytes.extend(sub_offsets);
-    bytes.extend(sub_names);
-    bytes.extend(sub_data);
-    bytes
+    w.bytes(&sub_names.into_bytes());
+    w.bytes(&sub_data);
+    w.into_bytes()
 }
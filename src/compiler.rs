@@ -0,0 +1,393 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+mod embedded;
+
+use crate::{
+    ast::{self, AstNode, Comment, Ecl, Layout},
+    code_gen,
+    ecl_instructions::InstructionSet,
+    error::{self, Diagnostic, Error, ErrorCode, Severity},
+    grammar::{self, Grammar, ProductionTable},
+    grammar_file::{self, GrammarFile, LexerRule},
+    lexer::{Lexer, SourceFile, SourceMap, Tokens, Trivia},
+    lint::{Lint, Lints},
+    parser::{self, ast::AstResolver},
+    scpt::Encoding,
+};
+
+/// What stays the same from one script to the other: the parser built from
+/// the grammar and the encoding.
+///
+/// The instructions are the built-in ones unless an eclmap is loaded in
+/// `instructions`, the warnings reported are the ones `lints` doesn't allow.
+/// A compiler is only read during the compilation, so the sessions of
+/// several threads can share it.
+pub struct Compiler {
+    table: ProductionTable,
+    lexer: Lexer<String>,
+    ast_resolver: AstResolver<AstNode>,
+    pub encoding: Encoding,
+    pub instructions: InstructionSet,
+    pub lints: Lints,
+}
+
+impl Compiler {
+    /// Uses the parser generated from the grammar of the language at build time.
+    pub fn new(encoding: Encoding) -> Self {
+        let mut ast_resolver = AstResolver::default();
+        ast_resolver.set_ast_prod(embedded::ast_prod());
+        ast::fill_executor(&mut ast_resolver);
+        Self {
+            table: embedded::production_table(),
+            lexer: embedded::lexer(),
+            ast_resolver,
+            encoding,
+            instructions: InstructionSet::default(),
+            lints: Lints::default(),
+        }
+    }
+
+    /// Builds the parser from the grammar file `grammar` instead, to try
+    /// changes of the language without rebuilding.
+    pub fn from_grammar(grammar: &str, encoding: Encoding) -> Result<Self, Error> {
+        let mut gf = GrammarFile::from_file(grammar).map_err(Error::IO)?;
+        let rulestrings = grammar_file::parse_rules(&mut gf);
+        let mut grammar = Grammar::from_rule_string(rulestrings);
+        grammar.calculate_first_sets();
+        grammar.calculate_follow_sets();
+
+        if !grammar::is_ll1_grammar(&grammar) {
+            return Err(Error::Grammar("The grammar is not LL1".to_owned()));
+        }
+
+        let mut ast_resolver = AstResolver::default();
+        ast_resolver.set_ast_prod(grammar.get_ast_prod());
+        ast::fill_executor(&mut ast_resolver);
+        Ok(Self {
+            table: grammar.fill_ll1_production_table(),
+            lexer: grammar_lexer(&gf),
+            ast_resolver,
+            encoding,
+            instructions: InstructionSet::default(),
+            lints: Lints::default(),
+        })
+    }
+
+    /// The tokens of `src`, for the tools which read a script as it is
+    /// written. With [`Tokens::with_trivia`], its comments and whitespace
+    /// are kept too.
+    pub fn tokens<'l>(&'l self, src: &SourceFile) -> Tokens<'l, String> {
+        self.lexer.tokens(src)
+    }
+}
+
+fn grammar_lexer(gf: &GrammarFile) -> Lexer<String> {
+    let mut lb = Lexer::builder();
+    for r in gf.lexer_rules() {
+        lb = match r {
+            LexerRule::Token {
+                regex,
+                kind,
+                escape,
+            } => lb.token(regex, kind.to_owned(), escape),
+            LexerRule::Eof(kind) => lb.eof(kind.to_owned()),
+            LexerRule::Error(kind) => lb.error(kind.to_owned()),
+            LexerRule::Ignore(regex) => lb.ignore(regex),
+        };
+    }
+    lb.build().expect("Incorrect lexer definition")
+}
+
+/// What the lexer ignores in `src` which the formatter keeps: the comments,
+/// from the trivia of the tokens, and the empty lines.
+///
+/// The formatter prints a comment between statements, so one inside a
+/// statement is moved after it, with a warning.
+fn layout(lexer: &Lexer<String>, src: &SourceFile) -> Layout {
+    let lines: Vec<String> = src.content.split('\n').map(str::to_owned).collect();
+    let mut comments = Vec::new();
+    let mut depth = 0;
+    let mut comment = |t: &Trivia, depth| {
+        if t.is_comment() {
+            let (line, col) = (t.loc.line, t.loc.span.start);
+            comments.push(Comment {
+                text: t.text.trim_end().to_owned(),
+                line,
+                col,
+                depth,
+                trailing: !lines[line][..col].trim().is_empty(),
+            });
+        }
+    };
+    let moved = |t: &Trivia| {
+        // without the newline ending a line comment
+        let mut loc = t.loc.clone();
+        loc.span.end = loc.span.start + t.text.trim_end().len();
+        error::warn(
+            Lint::MovedComments,
+            &loc,
+            "comment inside a statement is moved after it",
+            "moved after the statement",
+        )
+    };
+    // a comment is between statements after one of these tokens, or before
+    // a block, which it is printed at the start of
+    let mut parens = 0usize;
+    let mut prev = None;
+    let inside = |prev: Option<&str>, parens| {
+        parens > 0 || !matches!(prev, None | Some(";" | "lb" | "rb" | ":" | ","))
+    };
+    // the comments after a token, inside a statement unless a block follows
+    let mut pending: Vec<Trivia> = Vec::new();
+    for t in lexer.tokens(src).with_trivia() {
+        for l in pending.drain(..).filter(|_| t.kind != "lb") {
+            moved(&l);
+        }
+        for l in &t.leading {
+            comment(l, depth);
+            if l.is_comment() && t.kind != "lb" && inside(prev, parens) {
+                moved(l);
+            }
+        }
+        match t.kind {
+            "lb" => depth += 1,
+            "rb" => depth = depth.saturating_sub(1),
+            "(" => parens += 1,
+            ")" => parens = parens.saturating_sub(1),
+            _ => {}
+        }
+        prev = Some(t.kind);
+        for l in &t.trailing {
+            comment(l, depth);
+            if l.is_comment() && inside(prev, parens) {
+                pending.push(l.clone());
+            }
+        }
+    }
+    let empty_lines = (0..lines.len())
+        .filter(|l| lines[*l].trim().is_empty())
+        .collect();
+    Layout {
+        lines,
+        comments,
+        empty_lines,
+    }
+}
+
+/// Files being included, to detect the cycles, and files already included,
+/// which are only included once.
+#[derive(Default)]
+struct IncludeState {
+    stack: Vec<PathBuf>,
+    done: HashSet<PathBuf>,
+}
+
+/// Compilation of one or several scripts: the sources read, for the
+/// diagnostics, and the diagnostics reported.
+pub struct Session<'c> {
+    compiler: &'c Compiler,
+    sources: SourceMap,
+    diagnostics: Vec<Diagnostic>,
+    /// The levels of the compiler, unless changed for this session.
+    pub lints: Lints,
+    /// Lints already explained by a note, which is only shown once.
+    explained: HashSet<Lint>,
+}
+
+impl<'c> Session<'c> {
+    pub fn new(compiler: &'c Compiler) -> Self {
+        Self {
+            compiler,
+            sources: SourceMap::default(),
+            diagnostics: vec![],
+            lints: compiler.lints.clone(),
+            explained: HashSet::new(),
+        }
+    }
+
+    /// Compiles `src` to a SCPT file, or returns why it couldn't.
+    pub fn compile_str(&mut self, name: &str, src: &str) -> Result<Vec<u8>, Vec<Diagnostic>> {
+        let src = SourceFile::from_string(name, src.to_owned());
+        match self.compile(src) {
+            Ok(ecl) => Ok(self.generate(&ecl)),
+            Err(_) => Err(self.take_diagnostics()),
+        }
+    }
+
+    /// Parses `src` with its includes and processes it for binary generation.
+    /// The errors and warnings are kept in the diagnostics of the session,
+    /// a denied warning failing the compilation like an error.
+    pub fn compile(&mut self, src: SourceFile) -> Result<Ecl, Error> {
+        let (mut res, new) = error::collect(|| self.compile_inner(src));
+        let new = self.apply_lints(new);
+        if res.is_ok() && new.iter().any(|d| d.severity == Severity::Error) {
+            res = Err(Error::Simple("errors were reported".to_owned()));
+        }
+        self.diagnostics.extend(new);
+        res.inspect_err(|e| {
+            // the errors of the source have already been reported
+            let reported = matches!(e, Error::Simple(_))
+                && self
+                    .diagnostics
+                    .iter()
+                    .any(|d| d.severity == Severity::Error);
+            if !reported {
+                self.diagnostics.push(Diagnostic::from_error(e));
+            }
+        })
+    }
+
+    /// `new` at the levels of the lints of the session, the first warning of
+    /// each lint explaining its level.
+    fn apply_lints(&mut self, new: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let lints = &self.lints;
+        let mut new: Vec<_> = new.into_iter().filter_map(|d| lints.apply(d)).collect();
+        for d in &mut new {
+            if let Some(l) = d.lint.filter(|l| self.explained.insert(*l)) {
+                d.notes.push(lints.explain(l));
+            }
+        }
+        new
+    }
+
+    fn compile_inner(&mut self, src: SourceFile) -> Result<Ecl, Error> {
+        let path = PathBuf::from(&src.filename);
+        let mut node = self.parse_source(src)?;
+        let mut state = IncludeState::default();
+        if let Ok(p) = path.canonicalize() {
+            state.stack.push(p.clone());
+            state.done.insert(p);
+        }
+        self.expand_includes(&mut node, &path, &mut state)?;
+
+        // The enums declared before every script
+        let prelude = SourceFile::from_string("<prelude>", ast::PRELUDE.to_owned());
+        let prelude = self.parse_source(prelude)?.consts;
+
+        // Process code for binary generation
+        node.process(
+            &self.compiler.instructions,
+            self.compiler.encoding,
+            &prelude,
+        )?;
+        Ok(node)
+    }
+
+    /// Parses `src` as it is written, without its includes nor processing it,
+    /// for the tools that look at the names of a script. The syntax errors are
+    /// kept in the diagnostics of the session.
+    pub fn parse(&mut self, src: SourceFile) -> Result<Ecl, Error> {
+        let (res, new) = error::collect(|| self.parse_source(src));
+        self.diagnostics.extend(new);
+        res
+    }
+
+    /// `src` formatted, with its comments and its empty lines. A file with
+    /// syntax errors isn't formatted, the errors being kept in the
+    /// diagnostics of the session with the comments moved.
+    pub fn format(&mut self, src: SourceFile) -> Result<String, Error> {
+        let (layout, new) = error::collect(|| layout(&self.compiler.lexer, &src));
+        // the errors of the lexer are reported again by the parser
+        let moved: Vec<_> = new.into_iter().filter(|d| d.lint.is_some()).collect();
+        let before = self.diagnostics.len();
+        let moved = self.apply_lints(moved);
+        self.diagnostics.extend(moved);
+        let ecl = self.parse(src)?;
+        if self.diagnostics[before..]
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+        {
+            return Err(Error::Simple("errors were reported".to_owned()));
+        }
+        Ok(ast::format_ecl(&ecl, layout))
+    }
+
+    /// Binary of a compiled script.
+    pub fn generate(&self, ecl: &Ecl) -> Vec<u8> {
+        code_gen::generate(ecl, self.compiler.encoding)
+    }
+
+    /// Parses `src`, leaving its includes as they are.
+    fn parse_source(&mut self, src: SourceFile) -> Result<Ecl, Error> {
+        let src = self.sources.add(src);
+        let c = self.compiler;
+        Ok(c.ast_resolver
+            .resolve(
+                &parser::parse(&c.table, c.lexer.tokens(&src), "Ecl")
+                    .ok_or(Error::Simple("Could not parse node: Aborting".to_owned()))?,
+                &[],
+            )?
+            .ecl())
+    }
+
+    /// Replaces the includes of `ecl`, parsed from `path`, by the content of their
+    /// files. The paths are relative to the including file.
+    fn expand_includes(
+        &mut self,
+        ecl: &mut Ecl,
+        path: &Path,
+        state: &mut IncludeState,
+    ) -> Result<(), Error> {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut files = Vec::new();
+        for inc in &ecl.includes {
+            let file = dir.join(inc.path.val());
+            let canonical = file.canonicalize().map_err(|e| {
+                error::report_error_ext(
+                    ErrorCode::Include,
+                    inc.path.loc(),
+                    &format!("can't include `{}`: {e}", inc.path.val()),
+                    "included here",
+                );
+                Error::Simple("could not include a file".to_owned())
+            })?;
+            if state.stack.contains(&canonical) {
+                error::report_error_ext(
+                    ErrorCode::IncludeCycle,
+                    inc.path.loc(),
+                    &format!("including `{}` makes a cycle", inc.path.val()),
+                    "include cycle",
+                );
+                return Err(Error::Simple("include cycle".to_owned()));
+            }
+            if !state.done.insert(canonical.clone()) {
+                files.push(None);
+                continue;
+            }
+            let src = SourceFile::open(&file.to_string_lossy()).map_err(Error::IO)?;
+            let mut included = self.parse_source(src)?;
+            state.stack.push(canonical);
+            self.expand_includes(&mut included, &file, state)?;
+            state.stack.pop();
+            files.push(Some(included));
+        }
+        ecl.expand_includes(files);
+        Ok(())
+    }
+
+    /// The files read by the session, where its locations are.
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Terminal rendering of `d`, a diagnostic of this session.
+    pub fn render(&self, d: &Diagnostic) -> String {
+        d.render(&self.sources)
+    }
+
+    /// JSON rendering of `d`, a diagnostic of this session, on one line.
+    pub fn render_json(&self, d: &Diagnostic) -> String {
+        d.to_json(&self.sources).to_string()
+    }
+}
@@ -0,0 +1,13 @@
+//! The parser of the language, generated by build.rs from test.grammar.
+
+use std::collections::HashMap;
+
+use crate::{
+    grammar::{
+        micro_lang::{AstDef, Der},
+        ProductionTable, ProductionTableEntry, Symbol,
+    },
+    lexer::Lexer,
+};
+
+include!(concat!(env!("OUT_DIR"), "/grammar.rs"));
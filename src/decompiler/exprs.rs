@@ -0,0 +1,327 @@
+use super::lift::StackVars;
+use crate::ast::{Expr, ExprType, Instr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Or,
+    And,
+    Xor,
+    BinOr,
+    BinAnd,
+    Not,
+    Neg,
+    Sin,
+    Cos,
+    Sqrt,
+}
+
+/// Stack operations emitted by `Expr::instructions`, with the type of their operands.
+const OPS: &[(u16, Op, ExprType)] = &[
+    (50, Op::Add, ExprType::Int),
+    (51, Op::Add, ExprType::Float),
+    (52, Op::Sub, ExprType::Int),
+    (53, Op::Sub, ExprType::Float),
+    (54, Op::Mul, ExprType::Int),
+    (55, Op::Mul, ExprType::Float),
+    (56, Op::Div, ExprType::Int),
+    (57, Op::Div, ExprType::Float),
+    (58, Op::Mod, ExprType::Int),
+    (59, Op::Eq, ExprType::Int),
+    (60, Op::Eq, ExprType::Float),
+    (61, Op::Ne, ExprType::Int),
+    (62, Op::Ne, ExprType::Float),
+    (63, Op::Lt, ExprType::Int),
+    (64, Op::Lt, ExprType::Float),
+    (65, Op::Le, ExprType::Int),
+    (66, Op::Le, ExprType::Float),
+    (67, Op::Gt, ExprType::Int),
+    (68, Op::Gt, ExprType::Float),
+    (69, Op::Ge, ExprType::Int),
+    (70, Op::Ge, ExprType::Float),
+    (71, Op::Not, ExprType::Int),
+    (72, Op::Not, ExprType::Float),
+    (73, Op::Or, ExprType::Int),
+    (74, Op::And, ExprType::Int),
+    (75, Op::Xor, ExprType::Int),
+    (76, Op::BinOr, ExprType::Int),
+    (77, Op::BinAnd, ExprType::Int),
+    (79, Op::Sin, ExprType::Float),
+    (80, Op::Cos, ExprType::Float),
+    (83, Op::Neg, ExprType::Int),
+    (84, Op::Neg, ExprType::Float),
+    (88, Op::Sqrt, ExprType::Float),
+];
+
+impl Op {
+    fn is_unary(self) -> bool {
+        matches!(self, Op::Not | Op::Neg | Op::Sin | Op::Cos | Op::Sqrt)
+    }
+
+    fn result_type(self, operands: ExprType) -> ExprType {
+        match self {
+            Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge | Op::Not => ExprType::Int,
+            _ => operands,
+        }
+    }
+
+    fn unary(self, e: Expr) -> Expr {
+        let e = Box::new(e);
+        match self {
+            Op::Not => Expr::Not(e, None),
+            Op::Neg => Expr::Uminus(e, None),
+            Op::Sin => Expr::Sin(e, None),
+            Op::Cos => Expr::Cos(e, None),
+            Op::Sqrt => Expr::Sqrt(e, None),
+            _ => unreachable!("{self:?} is a binary operator"),
+        }
+    }
+
+    fn binary(self, l: Expr, r: Expr) -> Expr {
+        let (l, r) = (Box::new(l), Box::new(r));
+        match self {
+            Op::Add => Expr::Add(l, r, None),
+            Op::Sub => Expr::Sub(l, r, None),
+            Op::Mul => Expr::Mul(l, r, None),
+            Op::Div => Expr::Div(l, r, None),
+            Op::Mod => Expr::Modulo(l, r, None),
+            Op::Eq => Expr::Eq(l, r, None),
+            Op::Ne => Expr::Ne(l, r, None),
+            Op::Lt => Expr::Lt(l, r, None),
+            Op::Le => Expr::Le(l, r, None),
+            Op::Gt => Expr::Gt(l, r, None),
+            Op::Ge => Expr::Ge(l, r, None),
+            Op::Or => Expr::Or(l, r, None),
+            Op::And => Expr::And(l, r, None),
+            Op::Xor => Expr::Xor(l, r, None),
+            Op::BinOr => Expr::BinOr(l, r, None),
+            Op::BinAnd => Expr::BinAnd(l, r, None),
+            _ => unreachable!("{self:?} is a unary operator"),
+        }
+    }
+}
+
+pub fn opcode_of(name: &str) -> Option<u16> {
+    name.strip_prefix("ins_")?.parse().ok()
+}
+
+fn is_literal(e: &Expr) -> bool {
+    matches!(e, Expr::Int(_) | Expr::Float(_) | Expr::Str(_))
+}
+
+/// Whether constant folding leaves `e` untouched, i.e. whether the compiler
+/// would emit the exact same stack operations for it.
+fn is_folded(e: &Expr) -> bool {
+    match e {
+        Expr::Add(l, r, _)
+        | Expr::Sub(l, r, _)
+        | Expr::Mul(l, r, _)
+        | Expr::Div(l, r, _)
+        | Expr::Modulo(l, r, _)
+        | Expr::Gt(l, r, _)
+        | Expr::Ge(l, r, _)
+        | Expr::Lt(l, r, _)
+        | Expr::Le(l, r, _)
+        | Expr::Eq(l, r, _)
+        | Expr::Ne(l, r, _)
+        | Expr::BinAnd(l, r, _)
+        | Expr::BinOr(l, r, _)
+        | Expr::Xor(l, r, _)
+        | Expr::Or(l, r, _)
+        | Expr::And(l, r, _) => !(is_literal(l) && is_literal(r)) && is_folded(l) && is_folded(r),
+        Expr::Uminus(e, _)
+        | Expr::Not(e, _)
+        | Expr::Sin(e, _)
+        | Expr::Cos(e, _)
+        | Expr::Sqrt(e, _) => !is_literal(e) && is_folded(e),
+        _ => true,
+    }
+}
+
+/// An expression on the stack, built from the instructions starting at `start` in the output.
+struct Pending {
+    e: Expr,
+    typ: ExprType,
+    start: usize,
+}
+
+/// Stack slots read by a call, as `(argument index, type)`.
+/// They have to be `[-1]`, `[-2]`... in order, like `check_expressions` numbers them.
+fn stack_args(args: &[Expr]) -> Option<Vec<(usize, ExprType)>> {
+    let mut out = Vec::new();
+    for (i, a) in args.iter().enumerate() {
+        let (off, typ) = match a {
+            Expr::VarInt(v) if *v.val() < 0 => (*v.val(), ExprType::Int),
+            Expr::VarFloat(v) if *v.val() < 0.0 => {
+                if v.val().fract() != 0.0 {
+                    return None;
+                }
+                (*v.val() as i32, ExprType::Float)
+            }
+            _ => continue,
+        };
+        if off != -(out.len() as i32 + 1) {
+            return None;
+        }
+        out.push((i, typ));
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+/// Folds the stack pushes and operations preceding a consumer back into an expression:
+/// - `push; ...; ins_43/45 var` becomes `var = expr;`
+/// - a call reading `[-1]`, `[-2]`... takes the pushed expressions as arguments
+/// - a conditional jump is preceded by a `PushExpr` of its condition
+///
+/// Anything that would not compile back to the same instructions is left as is.
+pub fn recover(instrs: Vec<Instr>, vars: Option<&StackVars>) -> Vec<Instr> {
+    let mut out: Vec<Instr> = Vec::new();
+    let mut stack: Vec<Pending> = Vec::new();
+    for ins in instrs {
+        let (name, args) = match &ins {
+            Instr::Call(n, a) => (n.val().clone(), a.clone()),
+            _ => {
+                stack.clear();
+                out.push(ins);
+                continue;
+            }
+        };
+        let opcode = opcode_of(&name).unwrap_or(u16::MAX);
+
+        // calls reading expressions from the stack
+        if let Some(sa) = stack_args(&args).filter(|_| !matches!(opcode, 12..=14)) {
+            if sa.len() <= stack.len() {
+                let base = stack.len() - sa.len();
+                let fits = sa
+                    .iter()
+                    .zip(&stack[base..])
+                    .all(|((_, t), p)| p.typ == *t && !p.e.is_primitive() && is_folded(&p.e));
+                if fits {
+                    out.truncate(stack[base].start);
+                    let mut args = args;
+                    for ((i, _), p) in sa.iter().zip(stack.drain(base..)) {
+                        args[*i] = p.e;
+                    }
+                    out.push(Instr::Call(name.into(), args));
+                    stack.clear();
+                    continue;
+                }
+            }
+        }
+
+        let op = OPS.iter().find(|(oc, ..)| *oc == opcode);
+        match (opcode, &args[..], op) {
+            (42, [e @ (Expr::Int(_) | Expr::VarInt(_))], _) => {
+                stack.push(Pending {
+                    e: e.clone(),
+                    typ: ExprType::Int,
+                    start: out.len(),
+                });
+                out.push(ins);
+            }
+            (44, [e @ (Expr::Float(_) | Expr::VarFloat(_))], _) => {
+                stack.push(Pending {
+                    e: e.clone(),
+                    typ: ExprType::Float,
+                    start: out.len(),
+                });
+                out.push(ins);
+            }
+            (43 | 45, [var], _) => {
+                let typ = if opcode == 43 {
+                    ExprType::Int
+                } else {
+                    ExprType::Float
+                };
+                match (vars.and_then(|v| v.name(var)), stack.last()) {
+                    (Some(n), Some(p)) if p.typ == typ && is_folded(&p.e) => {
+                        let p = stack.pop().unwrap();
+                        out.truncate(p.start);
+                        out.push(Instr::Affect(n.to_owned().into(), p.e));
+                    }
+                    _ => {
+                        stack.clear();
+                        out.push(ins);
+                    }
+                }
+            }
+            (13 | 14, _, _) => {
+                match stack.pop() {
+                    Some(p) if p.typ == ExprType::Int && is_folded(&p.e) && !is_literal(&p.e) => {
+                        out.truncate(p.start);
+                        out.push(Instr::PushExpr(p.e));
+                    }
+                    _ => {}
+                }
+                stack.clear();
+                out.push(ins);
+            }
+            (_, [], Some(&(_, op, typ))) => {
+                let arity = if op.is_unary() { 1 } else { 2 };
+                if stack.len() < arity || stack[stack.len() - arity..].iter().any(|p| p.typ != typ)
+                {
+                    stack.clear();
+                    out.push(ins);
+                    continue;
+                }
+                let (e, start) = if op.is_unary() {
+                    let a = stack.pop().unwrap();
+                    (op.unary(a.e), a.start)
+                } else {
+                    let r = stack.pop().unwrap();
+                    let l = stack.pop().unwrap();
+                    (op.binary(l.e, r.e), l.start)
+                };
+                stack.push(Pending {
+                    e,
+                    typ: op.result_type(typ),
+                    start,
+                });
+                out.push(ins);
+            }
+            _ => {
+                stack.clear();
+                out.push(ins);
+            }
+        }
+    }
+    out
+}
+
+/// Turns the conditions that were not used by a control structure back into stack operations.
+pub fn lower_leftovers(instrs: &mut Vec<Instr>) {
+    let mut out = Vec::with_capacity(instrs.len());
+    for mut i in instrs.drain(..) {
+        match &mut i {
+            Instr::PushExpr(e) => {
+                let mut e = e.clone();
+                e.anotate().expect("recovered expressions are well typed");
+                out.extend(
+                    e.instructions()
+                        .expect("recovered expressions are well typed"),
+                );
+                continue;
+            }
+            Instr::If(_, a, b) => {
+                lower_leftovers(a);
+                lower_leftovers(b);
+            }
+            Instr::Bloc(b) | Instr::Loop(b) | Instr::While(_, b) | Instr::DoWhile(_, b) => {
+                lower_leftovers(b)
+            }
+            _ => {}
+        }
+        out.push(i);
+    }
+    *instrs = out;
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use super::exprs::{self, opcode_of};
+use super::reader::{RawEcl, RawInstr, RawSub};
+use super::structure;
+use crate::ast::{Ecl, Expr, Instr, Located, Sub, TimeLabelKind};
+use crate::ecl_instructions::{ArgType, InstructionSet, MatchInsResult};
+use crate::scpt::{self, Encoding, Reader};
+
+/// Decodes the arguments of `ins` according to `fmt`.
+/// Varargs are flattened into the returned list.
+fn decode_with(ins: &RawInstr, fmt: &[ArgType], encoding: Encoding) -> Option<Vec<Expr>> {
+    let mut r = Reader::new(&ins.args);
+    let mut out = Vec::new();
+    for (i, a) in fmt.iter().enumerate() {
+        let is_var = i < 16 && ins.param_mask & (1 << i) != 0;
+        match a {
+            ArgType::Int | ArgType::IntRef => {
+                let v = r.i32().ok()?;
+                out.push(if is_var {
+                    Expr::VarInt(v.into())
+                } else {
+                    Expr::Int(v.into())
+                });
+            }
+            ArgType::Float | ArgType::FloatRef => {
+                let v = r.f32().ok()?;
+                out.push(if is_var {
+                    Expr::VarFloat(v.into())
+                } else {
+                    Expr::Float(v.into())
+                });
+            }
+            ArgType::Str => {
+                let s = scpt::read_str_arg(&mut r).ok()?;
+                out.push(Expr::Str(encoding.decode(s).into()));
+            }
+            ArgType::Varargs => {
+                // each parameter has its own bit in the mask
+                while r.remaining() > 0 {
+                    // only the first two bytes of the tag are meaningful
+                    let tag = &r.bytes(4).ok()?[..2];
+                    let n = out.len();
+                    let is_var = n < 16 && ins.param_mask & (1 << n) != 0;
+                    if tag == &scpt::VARARG_INT[..2] {
+                        let v = r.i32().ok()?;
+                        out.push(if is_var {
+                            Expr::VarInt(v.into())
+                        } else {
+                            Expr::Int(v.into())
+                        });
+                    } else if tag == &scpt::VARARG_FLOAT[..2] {
+                        let v = r.f32().ok()?;
+                        out.push(if is_var {
+                            Expr::VarFloat(v.into())
+                        } else {
+                            Expr::Float(v.into())
+                        });
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+    if r.remaining() != 0 || out.len() != ins.param_count as usize {
+        return None;
+    }
+    Some(out)
+}
+
+fn decode_args(ins: &RawInstr, set: &InstructionSet, encoding: Encoding) -> Vec<Expr> {
+    for def in set.ins_defs_for_opcode(ins.opcode) {
+        if let Some(args) = decode_with(ins, def.arg_format(), encoding) {
+            return args;
+        }
+    }
+    // unknown signature: read everything as integers
+    ins.args
+        .chunks_exact(4)
+        .enumerate()
+        .map(|(i, c)| {
+            let v = i32::from_le_bytes(c.try_into().unwrap());
+            if i < 16 && ins.param_mask & (1 << i) != 0 {
+                Expr::VarInt(v.into())
+            } else {
+                Expr::Int(v.into())
+            }
+        })
+        .collect()
+}
+
+/// Name to use for the call: the first alternative name that the compiler
+/// would resolve back to the same opcode, or `ins_N`.
+fn ins_name(opcode: u16, args: &[Expr], set: &InstructionSet) -> String {
+    // these get their own syntax when printed
+    if [1, 10, 11, 12, 15, 16].contains(&opcode) {
+        return format!("ins_{opcode}");
+    }
+    // expressions are matched with their type, as `check_expressions` does
+    let mut args = args.to_vec();
+    for a in &mut args {
+        let _ = a.anotate();
+    }
+    for def in set.ins_defs_for_opcode(opcode) {
+        for alt in def.alt_names() {
+            match set.matching_ins_sep(alt, &args) {
+                Ok(MatchInsResult::Match(oc)) | Ok(MatchInsResult::MatchVA(oc, _))
+                    if oc == opcode =>
+                {
+                    return alt.to_string()
+                }
+                _ => {}
+            }
+        }
+    }
+    format!("ins_{opcode}")
+}
+
+fn is_jump(opcode: u16) -> bool {
+    matches!(opcode, 12..=14)
+}
+
+/// Names the stack slots allocated by the leading `ins_40` of a sub.
+pub struct StackVars {
+    size: i32,
+    names: HashMap<i32, (String, bool)>,
+}
+
+impl StackVars {
+    fn new(size: i32, instrs: &[(RawInstr, Vec<Expr>)]) -> Self {
+        // (int uses, float uses) for each slot
+        let mut uses: HashMap<i32, (bool, bool)> = HashMap::new();
+        for (_, args) in instrs {
+            for a in args {
+                match a {
+                    Expr::VarInt(i) => uses.entry(*i.val()).or_default().0 = true,
+                    Expr::VarFloat(f) if f.val().fract() == 0.0 => {
+                        uses.entry(*f.val() as i32).or_default().1 = true
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut names = HashMap::new();
+        for (n, o) in (0..size).step_by(4).enumerate() {
+            let (int_use, float_use) = uses.get(&o).copied().unwrap_or_default();
+            names.insert(o, (format!("var{n}"), float_use && !int_use));
+        }
+        Self { size, names }
+    }
+
+    fn decls(&self) -> Vec<Instr> {
+        (0..self.size)
+            .step_by(4)
+            .map(|o| {
+                let (n, is_float) = &self.names[&o];
+                if *is_float {
+                    Instr::VarFloat(n.clone().into(), None)
+                } else {
+                    Instr::VarInt(n.clone().into(), None)
+                }
+            })
+            .collect()
+    }
+
+    /// Name of the variable `e` refers to, if it has the type of the variable.
+    pub fn name(&self, e: &Expr) -> Option<&str> {
+        match e {
+            Expr::VarInt(i) => match self.names.get(i.val()) {
+                Some((n, false)) => Some(n),
+                _ => None,
+            },
+            Expr::VarFloat(f) if f.val().fract() == 0.0 => {
+                match self.names.get(&(*f.val() as i32)) {
+                    Some((n, true)) => Some(n),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn replace(&self, e: &mut Expr) {
+        e.visit_mut(&mut |e| {
+            if let Some(n) = self.name(e) {
+                *e = Expr::Id(n.to_owned().into());
+            }
+        });
+    }
+}
+
+/// Gives the calls their alternative names and the variables their names.
+fn name_instrs(instrs: &mut [Instr], set: &InstructionSet, vars: Option<&StackVars>) {
+    let replace = |e: &mut Expr| {
+        if let Some(sv) = vars {
+            sv.replace(e);
+        }
+    };
+    for i in instrs {
+        match i {
+            Instr::Call(n, args) => {
+                if let Some(opcode) = opcode_of(n.val()) {
+                    *n = ins_name(opcode, args, set).into();
+                }
+                args.iter_mut().for_each(replace);
+            }
+            Instr::Affect(_, e) => replace(e),
+            Instr::If(c, a, b) => {
+                replace(c);
+                name_instrs(a, set, vars);
+                name_instrs(b, set, vars);
+            }
+            Instr::While(c, b) | Instr::DoWhile(c, b) => {
+                replace(c);
+                name_instrs(b, set, vars);
+            }
+            Instr::Loop(b) | Instr::Bloc(b) => name_instrs(b, set, vars),
+            _ => {}
+        }
+    }
+}
+
+fn lift_sub(raw: &RawSub, set: &InstructionSet, encoding: Encoding) -> Sub {
+    let mut instrs: Vec<(RawInstr, Vec<Expr>)> = raw
+        .instrs
+        .iter()
+        .map(|i| (i.clone(), decode_args(i, set, encoding)))
+        .collect();
+
+    // labels for jump targets
+    let mut targets: Vec<usize> = instrs
+        .iter()
+        .filter(|(i, _)| is_jump(i.opcode))
+        .filter_map(|(i, a)| match a.first() {
+            Some(Expr::Int(rel)) => Some((i.offset as i64 + *rel.val() as i64) as usize),
+            _ => None,
+        })
+        .filter(|t| *t == raw.size || instrs.iter().any(|(i, _)| i.offset == *t))
+        .collect();
+    targets.sort();
+    targets.dedup();
+    let labels: HashMap<usize, String> = targets
+        .into_iter()
+        .enumerate()
+        .map(|(n, t)| (t, format!("label_{n}")))
+        .collect();
+
+    let mut out = Vec::new();
+    let stack_vars = match instrs.first() {
+        Some((i, a)) if i.opcode == 40 && i.time == 0 && i.rank_mask == 255 => match a[..] {
+            [Expr::Int(ref n)] if *n.val() >= 0 && n.val() % 4 == 0 => {
+                Some(StackVars::new(*n.val(), &instrs))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(sv) = &stack_vars {
+        out.extend(sv.decls());
+        instrs.remove(0);
+    }
+
+    let mut body = Vec::new();
+    let mut time = 0u32;
+    let mut rank = 255u8;
+    for (i, mut args) in instrs {
+        // time and rank changes go first, so that the labels stick to their instruction
+        if i.time != time {
+            let (d, k) = if i.time > time {
+                (i.time - time, TimeLabelKind::Add)
+            } else {
+                (time - i.time, TimeLabelKind::Sub)
+            };
+            body.push(Instr::TimeLabel(Located::from(d as i32), k));
+            time = i.time;
+        }
+        if i.rank_mask != rank {
+            body.push(Instr::RankLabel(i.rank_mask.into()));
+            rank = i.rank_mask;
+        }
+        if let Some(l) = labels.get(&i.offset) {
+            body.push(Instr::Label(l.clone().into()));
+        }
+        if is_jump(i.opcode) {
+            if let Some(Expr::Int(rel)) = args.first() {
+                let t = (i.offset as i64 + *rel.val() as i64) as usize;
+                if let Some(l) = labels.get(&t) {
+                    args[0] = Expr::Id(l.clone().into());
+                }
+            }
+        }
+        body.push(Instr::Call(format!("ins_{}", i.opcode).into(), args));
+    }
+    if let Some(l) = labels.get(&raw.size) {
+        body.push(Instr::Label(l.clone().into()));
+    }
+
+    let body = exprs::recover(body, stack_vars.as_ref());
+    let mut body = structure::recover(body);
+    exprs::lower_leftovers(&mut body);
+    name_instrs(&mut body, set, stack_vars.as_ref());
+    out.extend(body);
+
+    Sub {
+        name: raw.name.clone().into(),
+        params: vec![],
+        instructions: out,
+    }
+}
+
+/// `set` gives the signatures of the instructions, to decode their arguments.
+pub fn lift_ecl(raw: &RawEcl, set: &InstructionSet, encoding: Encoding) -> Ecl {
+    let names = |l: &[Vec<u8>]| l.iter().map(|n| encoding.decode(n).into()).collect();
+    Ecl {
+        ecli: names(&raw.ecli),
+        anmi: names(&raw.anmi),
+        includes: vec![],
+        consts: vec![],
+        enums: vec![],
+        subs: raw
+            .subs
+            .iter()
+            .map(|s| lift_sub(s, set, encoding))
+            .collect(),
+    }
+}
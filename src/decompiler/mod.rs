@@ -0,0 +1,52 @@
+mod exprs;
+mod lift;
+mod reader;
+mod structure;
+
+use crate::{ecl_instructions::InstructionSet, error::Error, scpt::Encoding};
+
+/// Turns a SCPT file back into eclc source, with the names and the
+/// signatures of `instructions`.
+pub fn decompile(
+    bytes: &[u8],
+    instructions: &InstructionSet,
+    encoding: Encoding,
+) -> Result<String, Error> {
+    let raw = reader::read_ecl(bytes)?;
+    let ecl = lift::lift_ecl(&raw, instructions, encoding);
+    Ok(crate::ast::print_ecl(&ecl))
+}
+
+/// Describes what is at `offset` in a SCPT file, for error messages.
+pub fn locate(bytes: &[u8], offset: usize) -> String {
+    let raw = match reader::read_ecl(bytes) {
+        Ok(raw) => raw,
+        Err(_) => return format!("byte {offset:#x}"),
+    };
+    for sub in &raw.subs {
+        if offset < sub.data_start || offset >= sub.data_start + sub.size {
+            continue;
+        }
+        let rel = offset - sub.data_start;
+        let Some((n, ins)) = raw_instr_at(&sub.instrs, rel) else {
+            break;
+        };
+        return format!(
+            "sub `{}`, instruction #{} (ins_{}) at offset {:#x}",
+            sub.name, n, ins.opcode, ins.offset
+        );
+    }
+    if offset >= bytes.len() {
+        "end of file".to_owned()
+    } else {
+        format!("header, byte {offset:#x}")
+    }
+}
+
+fn raw_instr_at(instrs: &[reader::RawInstr], rel: usize) -> Option<(usize, &reader::RawInstr)> {
+    instrs
+        .iter()
+        .enumerate()
+        .take_while(|(_, i)| i.offset <= rel)
+        .last()
+}
@@ -0,0 +1,124 @@
+use crate::{
+    error::Error,
+    scpt::{self, Header, InstrHeader, Reader, SubHeader},
+};
+
+/// An instruction as it is stored in the file, arguments left undecoded.
+#[derive(Debug, Clone)]
+pub struct RawInstr {
+    pub offset: usize,
+    pub time: u32,
+    pub opcode: u16,
+    pub param_mask: u16,
+    pub rank_mask: u8,
+    pub param_count: u8,
+    pub args: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RawSub {
+    pub name: String,
+    /// Position of the instruction data in the file.
+    pub data_start: usize,
+    pub instrs: Vec<RawInstr>,
+    /// Size of the instruction data.
+    pub size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RawEcl {
+    /// Include names, still encoded.
+    pub anmi: Vec<Vec<u8>>,
+    pub ecli: Vec<Vec<u8>>,
+    pub subs: Vec<RawSub>,
+}
+
+fn read_sub(bytes: &[u8], name: String, start: usize, end: usize) -> Result<RawSub, Error> {
+    if end > bytes.len() {
+        return Err(Error::Simple(format!(
+            "sub `{}` ends at offset {:#x}, past the end of the file ({:#x} bytes)",
+            name,
+            end,
+            bytes.len()
+        )));
+    }
+    let header = SubHeader::read(&mut Reader::at(&bytes[..end], start))?;
+    let data_start = start + header.data_offset as usize;
+    if data_start > end {
+        return Err(Error::Simple(format!(
+            "instructions of sub `{}` at offset {:#x}, past its end at {:#x}",
+            name, data_start, end
+        )));
+    }
+    let mut r = Reader::at(&bytes[..end], data_start);
+    let mut instrs = Vec::new();
+    while r.remaining() > 0 {
+        let offset = r.pos() - data_start;
+        let h = InstrHeader::read(&mut r)?;
+        if h.size < InstrHeader::SIZE {
+            return Err(Error::Simple(format!(
+                "instruction of size {} in sub `{}` at offset {:#x}",
+                h.size,
+                name,
+                data_start + offset
+            )));
+        }
+        let args = r.bytes((h.size - InstrHeader::SIZE) as usize)?.to_vec();
+        instrs.push(RawInstr {
+            offset,
+            time: h.time,
+            opcode: h.opcode,
+            param_mask: h.param_mask,
+            rank_mask: h.rank_mask,
+            param_count: h.param_count,
+            args,
+        });
+    }
+    Ok(RawSub {
+        name,
+        data_start,
+        instrs,
+        size: end - data_start,
+    })
+}
+
+/// Reads a SCPT file. This is the inverse of `code_gen::generate`.
+pub fn read_ecl(bytes: &[u8]) -> Result<RawEcl, Error> {
+    let header = Header::read(&mut Reader::new(bytes))?;
+    let include_offset = header.include_offset as usize;
+    let sub_count = header.sub_count as usize;
+
+    let mut r = Reader::at(bytes, include_offset);
+    let mut read_list = |magic| -> Result<Vec<Vec<u8>>, Error> {
+        Ok(scpt::read_inc_list(&mut r, magic)?
+            .into_iter()
+            .map(|s| s.to_vec())
+            .collect())
+    };
+    let anmi = read_list(scpt::ANIM_MAGIC)?;
+    let ecli = read_list(scpt::ECLI_MAGIC)?;
+
+    let mut r = Reader::at(bytes, include_offset + header.include_length as usize);
+    let mut offsets = Vec::new();
+    for _ in 0..sub_count {
+        offsets.push(r.u32()? as usize);
+    }
+    let mut names = Vec::new();
+    for _ in 0..sub_count {
+        names.push(String::from_utf8_lossy(r.cstr()?).into_owned());
+    }
+
+    let mut sorted = offsets.clone();
+    sorted.sort();
+    let mut subs = Vec::new();
+    for (name, start) in names.into_iter().zip(offsets) {
+        let end = sorted
+            .iter()
+            .find(|o| **o > start)
+            .copied()
+            .unwrap_or(bytes.len());
+        subs.push(read_sub(bytes, name, start, end)?);
+    }
+
+    Ok(RawEcl { anmi, ecli, subs })
+}
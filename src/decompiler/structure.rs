@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use crate::ast::{Expr, Instr};
+
+/// Target of `ins_N(label, 0.0f)`, the jumps emitted by the control flow desugaring.
+fn jump_to(i: &Instr, opcode: u16) -> Option<&str> {
+    match i {
+        Instr::Call(n, a) if *n.val() == format!("ins_{opcode}") => match &a[..] {
+            [Expr::Id(l), Expr::Float(t)] if t.val().to_bits() == 0 => Some(l.val()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn label_of(i: &Instr) -> Option<&str> {
+    match i {
+        Instr::Label(l) => Some(l.val()),
+        _ => None,
+    }
+}
+
+/// Time and rank labels can be moved across labels without changing the output.
+fn is_state_label(i: &Instr) -> bool {
+    matches!(i, Instr::TimeLabel(..) | Instr::RankLabel(_))
+}
+
+fn skip_state_labels(b: &[Instr], mut p: usize) -> usize {
+    while p < b.len() && is_state_label(&b[p]) {
+        p += 1;
+    }
+    p
+}
+
+/// Replaces the jumps to the start and the end of a loop by `continue` and `break`.
+/// Nested loops are not entered: their own break and continue target them.
+fn loop_jumps(b: &mut [Instr], cont: &str, brk: Option<&str>) {
+    for i in b {
+        if jump_to(i, 12) == Some(cont) {
+            *i = Instr::Continue;
+        } else if brk.is_some() && jump_to(i, 12) == brk {
+            *i = Instr::Break;
+        } else if let Instr::If(_, x, y) = i {
+            loop_jumps(x, cont, brk);
+            loop_jumps(y, cont, brk);
+        } else if let Instr::Bloc(x) = i {
+            loop_jumps(x, cont, brk);
+        }
+    }
+}
+
+/// Finds the control structures in a flat bloc.
+/// Labels are kept where they are, as other jumps may still target them.
+/// A label ending a bloc is also copied at the end of the bloc, for the nested
+/// structures ending there: the unused labels and the copies are removed afterwards.
+fn bloc(b: Vec<Instr>) -> Vec<Instr> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < b.len() {
+        let found = match &b[i] {
+            Instr::PushExpr(_) => if_else(&b, i),
+            Instr::Label(_) => loop_or_do_while(&b, i),
+            Instr::Call(..) => while_loop(&b, i),
+            _ => None,
+        };
+        match found {
+            Some((instrs, next)) => {
+                out.extend(instrs);
+                i = next;
+            }
+            None => {
+                out.push(b[i].clone());
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Builds a loop body and turns its jumps into `continue` and `break`.
+/// `after` is the index of the first instruction after the loop in `b`.
+fn loop_body(b: &[Instr], raw: Vec<Instr>, cont: &str, after: usize) -> Vec<Instr> {
+    let p = skip_state_labels(b, after);
+    let brk = b.get(p).and_then(label_of);
+    let mut body = bloc(raw);
+    loop_jumps(&mut body, cont, brk);
+    body
+}
+
+/// `ins_12 F; L: body F: push cond; ins_14 L;`
+fn while_loop(b: &[Instr], i: usize) -> Option<(Vec<Instr>, usize)> {
+    let first = jump_to(&b[i], 12)?;
+    let p = skip_state_labels(b, i + 1);
+    let lp = label_of(b.get(p)?)?;
+    let k = position_of(b, p + 1, first)?;
+    let q = skip_state_labels(b, k + 1);
+    let cond = match b.get(q) {
+        Some(Instr::PushExpr(c)) => c,
+        _ => return None,
+    };
+    if jump_to(b.get(q + 1)?, 14) != Some(lp) {
+        return None;
+    }
+    let mut raw: Vec<Instr> = b[i + 1..p].to_vec();
+    raw.extend_from_slice(&b[p + 1..q]);
+    let mut body = loop_body(b, raw, lp, q + 2);
+    body.insert(0, b[p].clone());
+    Some((vec![Instr::While(cond.clone(), body)], q + 2))
+}
+
+/// `L: body ins_12 L;` or `L: body push cond; ins_14 L;`
+fn loop_or_do_while(b: &[Instr], i: usize) -> Option<(Vec<Instr>, usize)> {
+    let lp = label_of(&b[i])?;
+    let back_jump = |j: &usize| match jump_to(&b[*j], 12) {
+        Some(l) => l == lp,
+        None => jump_to(&b[*j], 14) == Some(lp) && matches!(b[j - 1], Instr::PushExpr(_)),
+    };
+    let j = (i + 1..b.len()).rev().find(back_jump)?;
+    let looped = match (&b[j - 1], jump_to(&b[j], 14)) {
+        (Instr::PushExpr(c), Some(_)) => {
+            Instr::DoWhile(c.clone(), loop_body(b, b[i + 1..j - 1].to_vec(), lp, j + 1))
+        }
+        _ => Instr::Loop(loop_body(b, b[i + 1..j].to_vec(), lp, j + 1)),
+    };
+    Some((vec![b[i].clone(), looped], j + 1))
+}
+
+/// `push cond; ins_14 E; if E:` or `push cond; ins_14 E; if ins_12 D; E: else D:`
+fn if_else(b: &[Instr], i: usize) -> Option<(Vec<Instr>, usize)> {
+    let cond = match &b[i] {
+        Instr::PushExpr(c) => c,
+        _ => return None,
+    };
+    let else_lbl = jump_to(b.get(i + 1)?, 14)?;
+    let j = position_of(b, i + 2, else_lbl)?;
+    // the jump over the else bloc is the last instruction of the if bloc,
+    // and the compiler only emits it for a non empty else bloc
+    let d = (i + 2..j).rev().find(|&d| !is_state_label(&b[d]));
+    let endif = d.and_then(|d| {
+        let m = position_of(b, j + 1, jump_to(&b[d], 12)?)?;
+        (m > d + 2).then_some((d, m))
+    });
+    Some(match endif {
+        Some((d, m)) => {
+            let mut else_raw = b[d + 1..j].to_vec();
+            else_raw.extend_from_slice(&b[j..=m]);
+            let if_bloc = bloc(b[i + 2..d].to_vec());
+            let else_bloc = bloc(else_raw);
+            (vec![Instr::If(cond.clone(), if_bloc, else_bloc)], m)
+        }
+        None => {
+            let if_bloc = bloc(b[i + 2..=j].to_vec());
+            (vec![Instr::If(cond.clone(), if_bloc, vec![])], j)
+        }
+    })
+}
+
+fn position_of(b: &[Instr], from: usize, label: &str) -> Option<usize> {
+    (from..b.len()).find(|&p| label_of(&b[p]) == Some(label))
+}
+
+fn count_all_refs(instrs: &[Instr], refs: &mut HashMap<String, usize>) {
+    for i in instrs {
+        match i {
+            Instr::Call(_, a) => {
+                for e in a {
+                    if let Expr::Id(l) = e {
+                        *refs.entry(l.val().clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+            Instr::If(_, x, y) => {
+                count_all_refs(x, refs);
+                count_all_refs(y, refs);
+            }
+            Instr::Bloc(x) | Instr::Loop(x) | Instr::While(_, x) | Instr::DoWhile(_, x) => {
+                count_all_refs(x, refs)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Removes the labels nothing jumps to anymore and the copies of the others,
+/// and numbers them in order.
+fn clean_labels(
+    instrs: &mut Vec<Instr>,
+    refs: &HashMap<String, usize>,
+    names: &mut HashMap<String, String>,
+) {
+    let mut out = Vec::with_capacity(instrs.len());
+    for mut i in instrs.drain(..) {
+        match &mut i {
+            Instr::Label(l) => {
+                if !refs.contains_key(l.val()) || names.contains_key(l.val()) {
+                    continue;
+                }
+                let new = format!("label_{}", names.len());
+                names.insert(l.val().clone(), new.clone());
+                *l = new.into();
+            }
+            Instr::If(_, x, y) => {
+                clean_labels(x, refs, names);
+                clean_labels(y, refs, names);
+            }
+            Instr::Bloc(x) | Instr::Loop(x) | Instr::While(_, x) | Instr::DoWhile(_, x) => {
+                clean_labels(x, refs, names)
+            }
+            _ => {}
+        }
+        out.push(i);
+    }
+    *instrs = out;
+}
+
+fn rename_jumps(instrs: &mut [Instr], names: &HashMap<String, String>) {
+    for i in instrs {
+        match i {
+            Instr::Call(_, a) => {
+                for e in a {
+                    if let Expr::Id(l) = e {
+                        if let Some(n) = names.get(l.val()) {
+                            *l = n.clone().into();
+                        }
+                    }
+                }
+            }
+            Instr::If(_, x, y) => {
+                rename_jumps(x, names);
+                rename_jumps(y, names);
+            }
+            Instr::Bloc(x) | Instr::Loop(x) | Instr::While(_, x) | Instr::DoWhile(_, x) => {
+                rename_jumps(x, names)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rebuilds the `if`, `while`, `do while` and `loop` blocks from the jumps and labels
+/// `if_construct`, `while_construct` and `loop_construct` desugar them into.
+/// The conditions are expected as `PushExpr` right before their jump.
+pub fn recover(instrs: Vec<Instr>) -> Vec<Instr> {
+    let mut out = bloc(instrs);
+    let mut refs = HashMap::new();
+    count_all_refs(&out, &mut refs);
+    let mut names = HashMap::new();
+    clean_labels(&mut out, &refs, &mut names);
+    rename_jumps(&mut out, &names);
+    out
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use super::ArgType;
+use crate::error::Error;
+
+/// Instruction names and signatures read from a thtk style eclmap:
+///
+/// ```text
+/// !eclmap
+/// !ins_names
+/// 23 wait
+/// !ins_signatures
+/// 23 S
+/// 30 sD
+/// ```
+///
+/// Signature letters: `S` int, `$` int variable, `f` float, `%` float variable,
+/// `s` (or `m`, `z`) string and `D` for the variadic parameters.
+/// Sections other than `!ins_names` and `!ins_signatures` are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct EclMap {
+    pub names: BTreeMap<u16, Vec<String>>,
+    pub signatures: BTreeMap<u16, Vec<ArgType>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Names,
+    Signatures,
+    Other,
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut c = s.chars();
+    c.next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && c.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl EclMap {
+    pub fn open(filename: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(filename).map_err(Error::IO)?;
+        Self::parse(&content).map_err(|e| match e {
+            Error::Simple(s) => Error::Simple(format!("{filename}:{s}")),
+            e => e,
+        })
+    }
+
+    /// Parses an eclmap. Errors are prefixed by their line number.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut map = Self::default();
+        let mut section = None;
+        for (n, line) in content.lines().enumerate() {
+            let err = |msg: String| Error::Simple(format!("{}: {}", n + 1, msg));
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('!') {
+                section = match name {
+                    "eclmap" => section,
+                    "ins_names" => Some(Section::Names),
+                    "ins_signatures" => Some(Section::Signatures),
+                    _ => Some(Section::Other),
+                };
+                continue;
+            }
+            let section = match section {
+                None => return Err(err("expected a section such as `!ins_names`".to_owned())),
+                Some(Section::Other) => continue,
+                Some(s) => s,
+            };
+            let (opcode, value) = line
+                .split_once(char::is_whitespace)
+                .map(|(o, v)| (o, v.trim()))
+                .unwrap_or((line, ""));
+            let opcode: u16 = opcode
+                .parse()
+                .map_err(|_| err(format!("invalid opcode `{opcode}`")))?;
+            match section {
+                Section::Names => {
+                    if !is_ident(value) {
+                        return Err(err(format!("invalid instruction name `{value}`")));
+                    }
+                    map.names.entry(opcode).or_default().push(value.to_owned());
+                }
+                Section::Signatures => {
+                    let sig = value
+                        .chars()
+                        .map(|c| {
+                            ArgType::from_letter(c)
+                                .ok_or_else(|| err(format!("unknown argument type `{c}`")))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if sig[..sig.len().saturating_sub(1)].contains(&ArgType::Varargs) {
+                        return Err(err("`D` has to be the last argument".to_owned()));
+                    }
+                    map.signatures.insert(opcode, sig);
+                }
+                Section::Other => unreachable!(),
+            }
+        }
+        Ok(map)
+    }
+}
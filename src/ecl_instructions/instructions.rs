@@ -8,13 +8,29 @@ pub enum ArgType {
     Varargs,
 }
 
+impl ArgType {
+    /// Type of a signature letter in an eclmap.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'S' => Some(Self::Int),
+            '$' => Some(Self::IntRef),
+            'f' => Some(Self::Float),
+            '%' => Some(Self::FloatRef),
+            's' | 'm' | 'z' => Some(Self::Str),
+            'D' => Some(Self::Varargs),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InsDef {
     opcode: u16,
-    alt_names: Vec<&'static str>,
+    alt_names: Vec<String>,
     arg_format: Vec<ArgType>,
 }
 
+use super::EclMap;
 use crate::{
     ast::{Expr, ExprType},
     error::Error,
@@ -31,6 +47,14 @@ pub enum MatchType {
 }
 
 impl InsDef {
+    pub fn alt_names(&self) -> &[String] {
+        &self.alt_names
+    }
+
+    pub fn arg_format(&self) -> &[ArgType] {
+        &self.arg_format
+    }
+
     pub fn signature(&self) -> String {
         let mut s = format!("ins_{}", self.opcode);
         for alt in &self.alt_names {
@@ -64,13 +88,13 @@ impl InsDef {
         -1
     }
 
-    pub fn does_match(&self, name: &str, exprs: &Vec<Expr>) -> Result<MatchType, Error> {
+    pub fn does_match(&self, name: &str, exprs: &[Expr]) -> Result<MatchType, Error> {
         // has same name:
         let has_same_name = if name.starts_with("ins_") {
-            let opcode = name.strip_prefix("ins_").unwrap().parse().unwrap();
+            let opcode = name.strip_prefix("ins_").unwrap().parse::<u16>().unwrap();
             self.opcode == opcode
         } else {
-            self.alt_names.contains(&name)
+            self.alt_names.iter().any(|n| n == name)
         };
         if !has_same_name {
             return Ok(MatchType::NoMatch);
@@ -164,336 +188,416 @@ impl InsDef {
 }
 
 #[derive(Debug, Clone)]
-pub enum MatchInsResult {
+pub enum MatchInsResult<'s> {
     Match(u16),
     MatchVA(u16, usize),
-    NoMatch(Vec<NearMatch>),
+    NoMatch(Vec<NearMatch<'s>>),
 }
 
 #[derive(Debug, Clone)]
-pub struct NearMatch {
-    pub id: &'static InsDef,
+pub struct NearMatch<'s> {
+    pub id: &'s InsDef,
     pub mt: MatchType,
 }
 
-pub fn matching_ins_sep(name: &str, expr: &Vec<crate::ast::Expr>) -> Result<MatchInsResult, Error> {
-    let mut near_matches = Vec::new();
-    for i in INSTRUCTION_SET.iter() {
-        let matching = i.does_match(name, expr)?;
-        match matching {
-            MatchType::PerfectMatch => return Ok(MatchInsResult::Match(i.opcode)),
-            MatchType::WithVarargs(va) => return Ok(MatchInsResult::MatchVA(i.opcode, va)),
-            MatchType::NoMatch => {}
-            _ => near_matches.push(NearMatch {
-                id: i,
-                mt: matching,
-            }),
+/// The instructions known to a compiler: the built-in table, with the
+/// eclmaps loaded on top of it.
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    defs: Vec<InsDef>,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        Self {
+            defs: builtin_instructions(),
+        }
+    }
+}
+
+impl InstructionSet {
+    pub fn matching_ins_sep(&self, name: &str, expr: &[Expr]) -> Result<MatchInsResult<'_>, Error> {
+        let mut near_matches = Vec::new();
+        for i in &self.defs {
+            let matching = i.does_match(name, expr)?;
+            match matching {
+                MatchType::PerfectMatch => return Ok(MatchInsResult::Match(i.opcode)),
+                MatchType::WithVarargs(va) => return Ok(MatchInsResult::MatchVA(i.opcode, va)),
+                MatchType::NoMatch => {}
+                _ => near_matches.push(NearMatch {
+                    id: i,
+                    mt: matching,
+                }),
+            }
+        }
+        Ok(MatchInsResult::NoMatch(near_matches))
+    }
+
+    /// The names of the instructions, without their `ins_N` forms.
+    pub fn instruction_names(&self) -> impl Iterator<Item = &str> {
+        self.defs
+            .iter()
+            .flat_map(|i| i.alt_names.iter().map(String::as_str))
+    }
+
+    /// The instructions called `name`, an alternative name or `ins_N`.
+    pub fn ins_defs_for_name(&self, name: &str) -> Vec<&InsDef> {
+        if let Some(opcode) = name.strip_prefix("ins_").and_then(|n| n.parse().ok()) {
+            return self.ins_defs_for_opcode(opcode);
+        }
+        self.defs
+            .iter()
+            .filter(|i| i.alt_names.iter().any(|n| n == name))
+            .collect()
+    }
+
+    pub fn ins_defs_for_opcode(&self, opcode: u16) -> Vec<&InsDef> {
+        self.defs.iter().filter(|i| i.opcode == opcode).collect()
+    }
+
+    /// Merges `map` on top of the instructions.
+    /// An opcode with a signature in the map replaces all the definitions
+    /// of that opcode, an opcode with only names is renamed.
+    pub fn load_map(&mut self, map: &EclMap) -> Result<(), Error> {
+        let set = &mut self.defs;
+        for (opcode, sig) in &map.signatures {
+            let pos = set.iter().position(|i| i.opcode == *opcode);
+            let alt_names = map
+                .names
+                .get(opcode)
+                .cloned()
+                .or_else(|| pos.map(|p| set[p].alt_names.clone()))
+                .unwrap_or_default();
+            let def = InsDef {
+                opcode: *opcode,
+                alt_names,
+                arg_format: sig.clone(),
+            };
+            set.retain(|i| i.opcode != *opcode);
+            set.insert(pos.unwrap_or(set.len()), def);
+        }
+        for (opcode, names) in &map.names {
+            if map.signatures.contains_key(opcode) {
+                continue;
+            }
+            let mut found = false;
+            for i in set.iter_mut().filter(|i| i.opcode == *opcode) {
+                i.alt_names = names.clone();
+                found = true;
+            }
+            if !found {
+                return Err(Error::Simple(format!(
+                    "ins_{opcode} is named in the map but has no signature"
+                )));
+            }
         }
+        Ok(())
     }
-    Ok(MatchInsResult::NoMatch(near_matches))
 }
 
-lazy_static! {
-    static ref INSTRUCTION_SET: Vec<InsDef> = {
-        use ArgType as A;
-        let v = vec![
-        InsDef { opcode: 1, alt_names: vec![], arg_format: vec![], }, // delete is a keyword
-        InsDef { opcode: 10, alt_names: vec![], arg_format: vec![], }, // return is a keyword
-        InsDef { opcode: 11, alt_names: vec![], arg_format: vec![A::Str, A::Varargs], }, // use @ syntax
-        InsDef { opcode: 12, alt_names: vec!["jmp"], arg_format: vec![A::Int, A::Float], },
-        InsDef { opcode: 13, alt_names: vec!["jeq"], arg_format: vec![A::Int, A::Float], },
-        InsDef { opcode: 14, alt_names: vec!["jne"], arg_format: vec![A::Int, A::Float], },
-        InsDef { opcode: 15, alt_names: vec![], arg_format: vec![A::Str, A::Varargs], }, // @
-        InsDef { opcode: 16, alt_names: vec![], arg_format: vec![A::Str, A::Int, A::Varargs], }, // @
-        InsDef { opcode: 17, alt_names: vec!["killAsync"], arg_format: vec![A::Int], },
-        InsDef { opcode: 18, alt_names: vec![], arg_format: vec![A::Int], },
-        InsDef { opcode: 19, alt_names: vec![], arg_format: vec![A::Int], },
-        InsDef { opcode: 20, alt_names: vec![], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 21, alt_names: vec!["killAllAsync"], arg_format: vec![], },
-        InsDef { opcode: 22, alt_names: vec![], arg_format: vec![A::Int, A::Str], },
-        InsDef { opcode: 23, alt_names: vec!["wait"], arg_format: vec![A::Int], },
-        InsDef { opcode: 24, alt_names: vec!["wait"], arg_format: vec![A::Float], },
-        InsDef { opcode: 30, alt_names: vec!["printf"], arg_format: vec![A::Str, A::Varargs], },
-        InsDef { opcode: 31, alt_names: vec![], arg_format: vec![], },
-        InsDef { opcode: 40, alt_names: vec!["stackAlloc"], arg_format: vec![A::Int], },
-        InsDef { opcode: 41, alt_names: vec!["stackDealloc"], arg_format: vec![], },
-        InsDef { opcode: 42, alt_names: vec!["push"], arg_format: vec![A::Int], },
-        InsDef { opcode: 43, alt_names: vec!["set"], arg_format: vec![A::IntRef], },
-        InsDef { opcode: 44, alt_names: vec!["push"], arg_format: vec![A::Float], },
-        InsDef { opcode: 45, alt_names: vec!["set"], arg_format: vec![A::FloatRef], },
-        InsDef { opcode: 50, alt_names: vec!["addi"], arg_format: vec![], },
-        InsDef { opcode: 51, alt_names: vec!["addf"], arg_format: vec![], },
-        InsDef { opcode: 52, alt_names: vec!["subi"], arg_format: vec![], },
-        InsDef { opcode: 53, alt_names: vec!["subf"], arg_format: vec![], },
-        InsDef { opcode: 54, alt_names: vec!["muli"], arg_format: vec![], },
-        InsDef { opcode: 55, alt_names: vec!["mulf"], arg_format: vec![], },
-        InsDef { opcode: 56, alt_names: vec!["divi"], arg_format: vec![], },
-        InsDef { opcode: 57, alt_names: vec!["divf"], arg_format: vec![], },
-        InsDef { opcode: 58, alt_names: vec!["modi"], arg_format: vec![], },
-        InsDef { opcode: 59, alt_names: vec!["equi"], arg_format: vec![], },
-        InsDef { opcode: 60, alt_names: vec!["equf"], arg_format: vec![], },
-        InsDef { opcode: 61, alt_names: vec!["neqi"], arg_format: vec![], },
-        InsDef { opcode: 62, alt_names: vec!["neqf"], arg_format: vec![], },
-        InsDef { opcode: 63, alt_names: vec!["lesi"], arg_format: vec![], },
-        InsDef { opcode: 64, alt_names: vec!["lesf"], arg_format: vec![], },
-        InsDef { opcode: 65, alt_names: vec!["leqi"], arg_format: vec![], },
-        InsDef { opcode: 66, alt_names: vec!["leqf"], arg_format: vec![], },
-        InsDef { opcode: 67, alt_names: vec!["grei"], arg_format: vec![], },
-        InsDef { opcode: 68, alt_names: vec!["gref"], arg_format: vec![], },
-        InsDef { opcode: 69, alt_names: vec!["geqi"], arg_format: vec![], },
-        InsDef { opcode: 70, alt_names: vec!["geqf"], arg_format: vec![], },
-        InsDef { opcode: 71, alt_names: vec!["noti"], arg_format: vec![], },
-        InsDef { opcode: 72, alt_names: vec!["notf"], arg_format: vec![], },
-        InsDef { opcode: 73, alt_names: vec!["or"], arg_format: vec![], },
-        InsDef { opcode: 74, alt_names: vec!["and"], arg_format: vec![], },
-        InsDef { opcode: 75, alt_names: vec!["xor"], arg_format: vec![], },
-        InsDef { opcode: 76, alt_names: vec!["bor"], arg_format: vec![], },
-        InsDef { opcode: 77, alt_names: vec!["band"], arg_format: vec![], },
-        InsDef { opcode: 78, alt_names: vec!["deci"], arg_format: vec![A::IntRef], },
-        InsDef { opcode: 79, alt_names: vec!["ssin"], arg_format: vec![], },
-        InsDef { opcode: 80, alt_names: vec!["scos"], arg_format: vec![], },
-        InsDef { opcode: 83, alt_names: vec!["negi"], arg_format: vec![], },
-        InsDef { opcode: 84, alt_names: vec!["negf"], arg_format: vec![], },
-        InsDef { opcode: 88, alt_names: vec!["sqrt"], arg_format: vec![], },
-        InsDef { opcode: 82, alt_names: vec!["circlePos"], arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float], },
-        InsDef { opcode: 82, alt_names: vec!["validRad"], arg_format: vec![A::FloatRef], },
-        InsDef { opcode: 85, alt_names: vec!["sqSum"], arg_format: vec![A::FloatRef, A::Float, A::Float], },
-        InsDef { opcode: 86, alt_names: vec!["sqSumRt"], arg_format: vec![A::FloatRef, A::Float, A::Float], },
-        InsDef { opcode: 87, alt_names: vec!["getAng"], arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float], },
-        InsDef { opcode: 89, alt_names: vec!["linFunc"], arg_format: vec![A::FloatRef, A::Float, A::Float], },
-        InsDef { opcode: 90, alt_names: vec!["ptRot"], arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float, A::Float], },
-        InsDef { opcode: 91, alt_names: vec!["floatTime"], arg_format: vec![A::Int, A::FloatRef, A::Int, A::Int, A::Float, A::Float], },
-        InsDef { opcode: 92, alt_names: vec!["floatTimeEx"], arg_format: vec![A::Int, A::FloatRef, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float], },
-        InsDef { opcode: 93, alt_names: vec!["randRadius"], arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float], },
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[rustfmt::skip]
+fn builtin_instructions() -> Vec<InsDef> {
+    use ArgType as A;
+    vec![
+        InsDef { opcode: 1, alt_names: names(&[]), arg_format: vec![], }, // delete is a keyword
+        InsDef { opcode: 10, alt_names: names(&[]), arg_format: vec![], }, // return is a keyword
+        InsDef { opcode: 11, alt_names: names(&[]), arg_format: vec![A::Str, A::Varargs], }, // use @ syntax
+        InsDef { opcode: 12, alt_names: names(&["jmp"]), arg_format: vec![A::Int, A::Float], },
+        InsDef { opcode: 13, alt_names: names(&["jeq"]), arg_format: vec![A::Int, A::Float], },
+        InsDef { opcode: 14, alt_names: names(&["jne"]), arg_format: vec![A::Int, A::Float], },
+        InsDef { opcode: 15, alt_names: names(&[]), arg_format: vec![A::Str, A::Varargs], }, // @
+        InsDef { opcode: 16, alt_names: names(&[]), arg_format: vec![A::Str, A::Int, A::Varargs], }, // @
+        InsDef { opcode: 17, alt_names: names(&["killAsync"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 18, alt_names: names(&[]), arg_format: vec![A::Int], },
+        InsDef { opcode: 19, alt_names: names(&[]), arg_format: vec![A::Int], },
+        InsDef { opcode: 20, alt_names: names(&[]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 21, alt_names: names(&["killAllAsync"]), arg_format: vec![], },
+        InsDef { opcode: 22, alt_names: names(&[]), arg_format: vec![A::Int, A::Str], },
+        InsDef { opcode: 23, alt_names: names(&["wait"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 24, alt_names: names(&["wait"]), arg_format: vec![A::Float], },
+        InsDef { opcode: 30, alt_names: names(&["printf"]), arg_format: vec![A::Str, A::Varargs], },
+        InsDef { opcode: 31, alt_names: names(&[]), arg_format: vec![], },
+        InsDef { opcode: 40, alt_names: names(&["stackAlloc"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 41, alt_names: names(&["stackDealloc"]), arg_format: vec![], },
+        InsDef { opcode: 42, alt_names: names(&["push"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 43, alt_names: names(&["set"]), arg_format: vec![A::IntRef], },
+        InsDef { opcode: 44, alt_names: names(&["push"]), arg_format: vec![A::Float], },
+        InsDef { opcode: 45, alt_names: names(&["set"]), arg_format: vec![A::FloatRef], },
+        InsDef { opcode: 50, alt_names: names(&["addi"]), arg_format: vec![], },
+        InsDef { opcode: 51, alt_names: names(&["addf"]), arg_format: vec![], },
+        InsDef { opcode: 52, alt_names: names(&["subi"]), arg_format: vec![], },
+        InsDef { opcode: 53, alt_names: names(&["subf"]), arg_format: vec![], },
+        InsDef { opcode: 54, alt_names: names(&["muli"]), arg_format: vec![], },
+        InsDef { opcode: 55, alt_names: names(&["mulf"]), arg_format: vec![], },
+        InsDef { opcode: 56, alt_names: names(&["divi"]), arg_format: vec![], },
+        InsDef { opcode: 57, alt_names: names(&["divf"]), arg_format: vec![], },
+        InsDef { opcode: 58, alt_names: names(&["modi"]), arg_format: vec![], },
+        InsDef { opcode: 59, alt_names: names(&["equi"]), arg_format: vec![], },
+        InsDef { opcode: 60, alt_names: names(&["equf"]), arg_format: vec![], },
+        InsDef { opcode: 61, alt_names: names(&["neqi"]), arg_format: vec![], },
+        InsDef { opcode: 62, alt_names: names(&["neqf"]), arg_format: vec![], },
+        InsDef { opcode: 63, alt_names: names(&["lesi"]), arg_format: vec![], },
+        InsDef { opcode: 64, alt_names: names(&["lesf"]), arg_format: vec![], },
+        InsDef { opcode: 65, alt_names: names(&["leqi"]), arg_format: vec![], },
+        InsDef { opcode: 66, alt_names: names(&["leqf"]), arg_format: vec![], },
+        InsDef { opcode: 67, alt_names: names(&["grei"]), arg_format: vec![], },
+        InsDef { opcode: 68, alt_names: names(&["gref"]), arg_format: vec![], },
+        InsDef { opcode: 69, alt_names: names(&["geqi"]), arg_format: vec![], },
+        InsDef { opcode: 70, alt_names: names(&["geqf"]), arg_format: vec![], },
+        InsDef { opcode: 71, alt_names: names(&["noti"]), arg_format: vec![], },
+        InsDef { opcode: 72, alt_names: names(&["notf"]), arg_format: vec![], },
+        InsDef { opcode: 73, alt_names: names(&["or"]), arg_format: vec![], },
+        InsDef { opcode: 74, alt_names: names(&["and"]), arg_format: vec![], },
+        InsDef { opcode: 75, alt_names: names(&["xor"]), arg_format: vec![], },
+        InsDef { opcode: 76, alt_names: names(&["bor"]), arg_format: vec![], },
+        InsDef { opcode: 77, alt_names: names(&["band"]), arg_format: vec![], },
+        InsDef { opcode: 78, alt_names: names(&["deci"]), arg_format: vec![A::IntRef], },
+        InsDef { opcode: 79, alt_names: names(&["ssin"]), arg_format: vec![], },
+        InsDef { opcode: 80, alt_names: names(&["scos"]), arg_format: vec![], },
+        InsDef { opcode: 83, alt_names: names(&["negi"]), arg_format: vec![], },
+        InsDef { opcode: 84, alt_names: names(&["negf"]), arg_format: vec![], },
+        InsDef { opcode: 88, alt_names: names(&["sqrt"]), arg_format: vec![], },
+        InsDef { opcode: 82, alt_names: names(&["circlePos"]), arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float], },
+        InsDef { opcode: 82, alt_names: names(&["validRad"]), arg_format: vec![A::FloatRef], },
+        InsDef { opcode: 85, alt_names: names(&["sqSum"]), arg_format: vec![A::FloatRef, A::Float, A::Float], },
+        InsDef { opcode: 86, alt_names: names(&["sqSumRt"]), arg_format: vec![A::FloatRef, A::Float, A::Float], },
+        InsDef { opcode: 87, alt_names: names(&["getAng"]), arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float], },
+        InsDef { opcode: 89, alt_names: names(&["linFunc"]), arg_format: vec![A::FloatRef, A::Float, A::Float], },
+        InsDef { opcode: 90, alt_names: names(&["ptRot"]), arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float, A::Float], },
+        InsDef { opcode: 91, alt_names: names(&["floatTime"]), arg_format: vec![A::Int, A::FloatRef, A::Int, A::Int, A::Float, A::Float], },
+        InsDef { opcode: 92, alt_names: names(&["floatTimeEx"]), arg_format: vec![A::Int, A::FloatRef, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float], },
+        InsDef { opcode: 93, alt_names: names(&["randRadius"]), arg_format: vec![A::FloatRef, A::FloatRef, A::Float, A::Float], },
 
-        InsDef { opcode: 300, alt_names: vec!["enmCreate"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 301, alt_names: vec!["enmCreateA"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 302, alt_names: vec!["anmSelect"], arg_format: vec![A::Int], },
-        InsDef { opcode: 303, alt_names: vec!["anmSetSpr"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 304, alt_names: vec!["enmCreateM"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 305, alt_names: vec!["enmCreateAM"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 306, alt_names: vec!["anmSetMain"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 307, alt_names: vec!["anmPlay"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 308, alt_names: vec!["anmPlayAbs"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 309, alt_names: vec!["enmCreateF"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 310, alt_names: vec!["enmCreateAF"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 311, alt_names: vec!["enmCreateMF"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 312, alt_names: vec!["enmCreateAMF"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 313, alt_names: vec!["anmSelPlay"], arg_format: vec![A::Int], },
-        InsDef { opcode: 314, alt_names: vec!["anmPlayHigh"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 315, alt_names: vec!["anmPlayRotate"], arg_format: vec![A::Int, A::Int, A::Float], },
-        InsDef { opcode: 316, alt_names: vec!["anm316"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 317, alt_names: vec!["anmSwitch"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 318, alt_names: vec!["anmReset"], arg_format: vec![], },
-        InsDef { opcode: 319, alt_names: vec!["anmRotate"], arg_format: vec![A::Int, A::Float], },
-        InsDef { opcode: 320, alt_names: vec!["anmMove"], arg_format: vec![A::Int, A::Float, A::Float], },
-        InsDef { opcode: 321, alt_names: vec!["enmMapleEnemy"], arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 322, alt_names: vec!["enm322"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 323, alt_names: vec!["deathAnm"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 324, alt_names: vec!["enmPos2"], arg_format: vec![A::FloatRef, A::FloatRef, A::Int], },
-        InsDef { opcode: 325, alt_names: vec!["anmCol"], arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 326, alt_names: vec!["anmColT"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 327, alt_names: vec!["anmAlpha"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 328, alt_names: vec!["anmAlphaT"], arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 329, alt_names: vec!["anmScale"], arg_format: vec![A::Int, A::Float, A::Float], },
-        InsDef { opcode: 330, alt_names: vec!["anmScaleT"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float], },
-        InsDef { opcode: 331, alt_names: vec!["anmAlpha2"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 332, alt_names: vec!["anmAlpha2T"], arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
-        InsDef { opcode: 333, alt_names: vec!["anmPosT"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float], },
-        InsDef { opcode: 334, alt_names: vec!["anm334"], arg_format: vec![A::Int], },
-        InsDef { opcode: 335, alt_names: vec!["anmScale2"], arg_format: vec![A::Int, A::Float, A::Float], },
-        InsDef { opcode: 336, alt_names: vec!["anmLayer"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 337, alt_names: vec!["anmBM_16_anmPlayPos"], arg_format: vec![A::Int, A::Int], },
-        InsDef { opcode: 338, alt_names: vec!["anmPlayPos"], arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float], },
-        InsDef { opcode: 339, alt_names: vec!["anm339"], arg_format: vec![A::Int, A::Int, A::Int], },
-        InsDef { opcode: 340, alt_names: vec!["enmDelete"], arg_format: vec![A::Int], },
+        InsDef { opcode: 300, alt_names: names(&["enmCreate"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 301, alt_names: names(&["enmCreateA"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 302, alt_names: names(&["anmSelect"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 303, alt_names: names(&["anmSetSpr"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 304, alt_names: names(&["enmCreateM"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 305, alt_names: names(&["enmCreateAM"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 306, alt_names: names(&["anmSetMain"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 307, alt_names: names(&["anmPlay"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 308, alt_names: names(&["anmPlayAbs"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 309, alt_names: names(&["enmCreateF"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 310, alt_names: names(&["enmCreateAF"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 311, alt_names: names(&["enmCreateMF"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 312, alt_names: names(&["enmCreateAMF"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 313, alt_names: names(&["anmSelPlay"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 314, alt_names: names(&["anmPlayHigh"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 315, alt_names: names(&["anmPlayRotate"]), arg_format: vec![A::Int, A::Int, A::Float], },
+        InsDef { opcode: 316, alt_names: names(&["anm316"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 317, alt_names: names(&["anmSwitch"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 318, alt_names: names(&["anmReset"]), arg_format: vec![], },
+        InsDef { opcode: 319, alt_names: names(&["anmRotate"]), arg_format: vec![A::Int, A::Float], },
+        InsDef { opcode: 320, alt_names: names(&["anmMove"]), arg_format: vec![A::Int, A::Float, A::Float], },
+        InsDef { opcode: 321, alt_names: names(&["enmMapleEnemy"]), arg_format: vec![A::Str, A::Float, A::Float, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 322, alt_names: names(&["enm322"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 323, alt_names: names(&["deathAnm"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 324, alt_names: names(&["enmPos2"]), arg_format: vec![A::FloatRef, A::FloatRef, A::Int], },
+        InsDef { opcode: 325, alt_names: names(&["anmCol"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 326, alt_names: names(&["anmColT"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 327, alt_names: names(&["anmAlpha"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 328, alt_names: names(&["anmAlphaT"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 329, alt_names: names(&["anmScale"]), arg_format: vec![A::Int, A::Float, A::Float], },
+        InsDef { opcode: 330, alt_names: names(&["anmScaleT"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float], },
+        InsDef { opcode: 331, alt_names: names(&["anmAlpha2"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 332, alt_names: names(&["anmAlpha2T"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int], },
+        InsDef { opcode: 333, alt_names: names(&["anmPosT"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float], },
+        InsDef { opcode: 334, alt_names: names(&["anm334"]), arg_format: vec![A::Int], },
+        InsDef { opcode: 335, alt_names: names(&["anmScale2"]), arg_format: vec![A::Int, A::Float, A::Float], },
+        InsDef { opcode: 336, alt_names: names(&["anmLayer"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 337, alt_names: names(&["anmBM_16_anmPlayPos"]), arg_format: vec![A::Int, A::Int], },
+        InsDef { opcode: 338, alt_names: names(&["anmPlayPos"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float], },
+        InsDef { opcode: 339, alt_names: names(&["anm339"]), arg_format: vec![A::Int, A::Int, A::Int], },
+        InsDef { opcode: 340, alt_names: names(&["enmDelete"]), arg_format: vec![A::Int], },
 
-        InsDef { opcode: 400, alt_names: vec!["movePos"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 401, alt_names: vec!["movePosTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 402, alt_names: vec!["movePosRel"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 403, alt_names: vec!["movePosRelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 404, alt_names: vec!["moveVel"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 405, alt_names: vec!["moveVelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 406, alt_names: vec!["moveVelRel"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 407, alt_names: vec!["moveVelRelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 408, alt_names: vec!["moveCirc"], arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 409, alt_names: vec!["moveCircTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 410, alt_names: vec!["moveCircRel"], arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 411, alt_names: vec!["moveCircRelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 412, alt_names: vec!["moveRand"], arg_format: vec![A::Int, A::Int, A::Float] },
-        InsDef { opcode: 413, alt_names: vec!["moveRandRel"], arg_format: vec![A::Int, A::Int, A::Float] },
-        InsDef { opcode: 414, alt_names: vec!["moveBoss"], arg_format: vec![] },
-        InsDef { opcode: 415, alt_names: vec!["moveBossRel"], arg_format: vec![] },
-        InsDef { opcode: 416, alt_names: vec!["movePos3d"], arg_format: vec![A::Float, A::Float, A::Float] },
-        InsDef { opcode: 417, alt_names: vec!["movePos3dRel"], arg_format: vec![A::Float, A::Float, A::Float] },
-        InsDef { opcode: 418, alt_names: vec!["moveAdd"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 419, alt_names: vec!["moveAddRel"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 420, alt_names: vec!["moveEll"], arg_format: vec![A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 421, alt_names: vec!["moveEllTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 422, alt_names: vec!["moveEllRel"], arg_format: vec![A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 423, alt_names: vec!["moveEllRelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 424, alt_names: vec!["moveMirror"], arg_format: vec![A::Int] },
-        InsDef { opcode: 425, alt_names: vec!["moveBezier"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 426, alt_names: vec!["moveBezierRel"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 427, alt_names: vec!["moveReset"], arg_format: vec![] },
-        InsDef { opcode: 428, alt_names: vec!["moveVelNM"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 429, alt_names: vec!["moveVelTimeNM"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 430, alt_names: vec!["moveVelRelNM"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 431, alt_names: vec!["moveVelRelTimeNM"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 432, alt_names: vec!["moveEnm"], arg_format: vec![A::Int] },
-        InsDef { opcode: 433, alt_names: vec!["moveEnmRel"], arg_format: vec![A::Int] },
-        InsDef { opcode: 434, alt_names: vec!["moveCurve"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 435, alt_names: vec!["moveCurveRel"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 436, alt_names: vec!["moveAddTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 437, alt_names: vec!["moveAddRelTime"], arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 438, alt_names: vec!["moveCurveAdd"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 439, alt_names: vec!["moveCurveAddRel"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
-        InsDef { opcode: 440, alt_names: vec!["moveAngle"], arg_format: vec![A::Float] },
-        InsDef { opcode: 441, alt_names: vec!["moveAngleTime"], arg_format: vec![A::Int, A::Int, A::Float] },
-        InsDef { opcode: 442, alt_names: vec!["moveAngleRel"], arg_format: vec![A::Float] },
-        InsDef { opcode: 443, alt_names: vec!["moveAngleRelTime"], arg_format: vec![A::Int, A::Int, A::Float] },
-        InsDef { opcode: 444, alt_names: vec!["moveSpeed"], arg_format: vec![A::Float] },
-        InsDef { opcode: 445, alt_names: vec!["moveSpeedTime"], arg_format: vec![A::Int, A::Int, A::Float] },
-        InsDef { opcode: 446, alt_names: vec!["moveSpeedRel"], arg_format: vec![A::Float], },
-        InsDef { opcode: 447, alt_names: vec!["moveSpeedRelTime"], arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 400, alt_names: names(&["movePos"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 401, alt_names: names(&["movePosTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 402, alt_names: names(&["movePosRel"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 403, alt_names: names(&["movePosRelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 404, alt_names: names(&["moveVel"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 405, alt_names: names(&["moveVelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 406, alt_names: names(&["moveVelRel"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 407, alt_names: names(&["moveVelRelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 408, alt_names: names(&["moveCirc"]), arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 409, alt_names: names(&["moveCircTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 410, alt_names: names(&["moveCircRel"]), arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 411, alt_names: names(&["moveCircRelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 412, alt_names: names(&["moveRand"]), arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 413, alt_names: names(&["moveRandRel"]), arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 414, alt_names: names(&["moveBoss"]), arg_format: vec![] },
+        InsDef { opcode: 415, alt_names: names(&["moveBossRel"]), arg_format: vec![] },
+        InsDef { opcode: 416, alt_names: names(&["movePos3d"]), arg_format: vec![A::Float, A::Float, A::Float] },
+        InsDef { opcode: 417, alt_names: names(&["movePos3dRel"]), arg_format: vec![A::Float, A::Float, A::Float] },
+        InsDef { opcode: 418, alt_names: names(&["moveAdd"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 419, alt_names: names(&["moveAddRel"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 420, alt_names: names(&["moveEll"]), arg_format: vec![A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 421, alt_names: names(&["moveEllTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 422, alt_names: names(&["moveEllRel"]), arg_format: vec![A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 423, alt_names: names(&["moveEllRelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 424, alt_names: names(&["moveMirror"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 425, alt_names: names(&["moveBezier"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 426, alt_names: names(&["moveBezierRel"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 427, alt_names: names(&["moveReset"]), arg_format: vec![] },
+        InsDef { opcode: 428, alt_names: names(&["moveVelNM"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 429, alt_names: names(&["moveVelTimeNM"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 430, alt_names: names(&["moveVelRelNM"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 431, alt_names: names(&["moveVelRelTimeNM"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 432, alt_names: names(&["moveEnm"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 433, alt_names: names(&["moveEnmRel"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 434, alt_names: names(&["moveCurve"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 435, alt_names: names(&["moveCurveRel"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 436, alt_names: names(&["moveAddTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 437, alt_names: names(&["moveAddRelTime"]), arg_format: vec![A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 438, alt_names: names(&["moveCurveAdd"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 439, alt_names: names(&["moveCurveAddRel"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float] },
+        InsDef { opcode: 440, alt_names: names(&["moveAngle"]), arg_format: vec![A::Float] },
+        InsDef { opcode: 441, alt_names: names(&["moveAngleTime"]), arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 442, alt_names: names(&["moveAngleRel"]), arg_format: vec![A::Float] },
+        InsDef { opcode: 443, alt_names: names(&["moveAngleRelTime"]), arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 444, alt_names: names(&["moveSpeed"]), arg_format: vec![A::Float] },
+        InsDef { opcode: 445, alt_names: names(&["moveSpeedTime"]), arg_format: vec![A::Int, A::Int, A::Float] },
+        InsDef { opcode: 446, alt_names: names(&["moveSpeedRel"]), arg_format: vec![A::Float], },
+        InsDef { opcode: 447, alt_names: names(&["moveSpeedRelTime"]), arg_format: vec![A::Int, A::Int, A::Float] },
 
-        InsDef { opcode: 500, alt_names: vec!["setHurtbox"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 501, alt_names: vec!["setHitbox"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 502, alt_names: vec!["flagSet"], arg_format: vec![A::Int] },
-        InsDef { opcode: 503, alt_names: vec!["flagClear"], arg_format: vec![A::Int] },
-        InsDef { opcode: 504, alt_names: vec!["moveLimit"], arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
-        InsDef { opcode: 505, alt_names: vec!["moveLimitReset"], arg_format: vec![] },
-        InsDef { opcode: 506, alt_names: vec!["dropClear"], arg_format: vec![] },
-        InsDef { opcode: 507, alt_names: vec!["dropExtra"], arg_format: vec![A::Int, A::Int] },
-        InsDef { opcode: 508, alt_names: vec!["dropArea"], arg_format: vec![A::Float, A::Float] },
-        InsDef { opcode: 509, alt_names: vec!["dropItems"], arg_format: vec![] },
-        InsDef { opcode: 510, alt_names: vec!["dropMain"], arg_format: vec![A::Int] },
-        InsDef { opcode: 511, alt_names: vec!["lifeSet"], arg_format: vec![A::Int] },
-        InsDef { opcode: 512, alt_names: vec!["setBoss"], arg_format: vec![A::Int] },
-        InsDef { opcode: 513, alt_names: vec!["timerReset"], arg_format: vec![] },
-        InsDef { opcode: 514, alt_names: vec!["setInterrupt"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]},
-        InsDef { opcode: 515, alt_names: vec!["setInvuln"], arg_format: vec![A::Int]},
-        InsDef { opcode: 516, alt_names: vec!["playSound"], arg_format: vec![A::Int]},
-        InsDef { opcode: 517, alt_names: vec!["setScreenShake"], arg_format: vec![A::Int, A::Int, A::Int]},
-        InsDef { opcode: 518, alt_names: vec!["dialogueRead"], arg_format: vec![A::Int]},
-        InsDef { opcode: 519, alt_names: vec!["dialogueWait"], arg_format: vec![]},
-        InsDef { opcode: 520, alt_names: vec!["bossWait"], arg_format: vec![]},
-        InsDef { opcode: 521, alt_names: vec!["setTimeout"], arg_format: vec![A::Int, A::Str]},
-        InsDef { opcode: 522, alt_names: vec!["spellEx"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
-        InsDef { opcode: 523, alt_names: vec!["spellEnd"], arg_format: vec![]},
-        InsDef { opcode: 524, alt_names: vec!["setChapter"], arg_format: vec![A::Int]},
-        InsDef { opcode: 525, alt_names: vec!["enmKillAll"], arg_format: vec![]},
-        InsDef { opcode: 526, alt_names: vec!["etProtectRange"], arg_format: vec![A::Float]},
-        InsDef { opcode: 527, alt_names: vec!["lifeMarker"], arg_format: vec![A::Int, A::Float, A::Int]},
-        InsDef { opcode: 528, alt_names: vec!["spellUnused"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
-        InsDef { opcode: 529, alt_names: vec!["rankF3"], arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 530, alt_names: vec!["rankF5"], arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 531, alt_names: vec!["rankF2"], arg_format: vec![A::FloatRef, A::Float, A::Float]},
-        InsDef { opcode: 532, alt_names: vec!["rankI3"], arg_format: vec![A::IntRef, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 533, alt_names: vec!["rankI5"], arg_format: vec![A::IntRef, A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 534, alt_names: vec!["rankI2"], arg_format: vec![A::IntRef, A::Int, A::Int]},
-        InsDef { opcode: 535, alt_names: vec!["diffI"], arg_format: vec![A::IntRef, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 536, alt_names: vec!["diffF"], arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 537, alt_names: vec!["spell"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
-        InsDef { opcode: 538, alt_names: vec!["spell2"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
-        InsDef { opcode: 539, alt_names: vec!["spell3"], arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
-        InsDef { opcode: 540, alt_names: vec!["stars"], arg_format: vec![A::Int]},
-        InsDef { opcode: 541, alt_names: vec!["noHbDur"], arg_format: vec![A::Int]},
-        InsDef { opcode: 542, alt_names: vec!["spellTimeout"], arg_format: vec![]},
-        InsDef { opcode: 543, alt_names: vec!["unknown543"], arg_format: vec![]},
-        InsDef { opcode: 544, alt_names: vec!["unknown544"], arg_format: vec![A::Int]},
-        InsDef { opcode: 545, alt_names: vec!["laserCancel"], arg_format: vec![]},
-        InsDef { opcode: 546, alt_names: vec!["bombShield"], arg_format: vec![A::Int, A::Int]},
-        InsDef { opcode: 547, alt_names: vec!["gameSpeed"], arg_format: vec![A::Float]},
-        InsDef { opcode: 548, alt_names: vec!["diffWait"], arg_format: vec![A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 549, alt_names: vec!["unknown549"], arg_format: vec![A::Int]},
-        InsDef { opcode: 550, alt_names: vec!["unknown550"], arg_format: vec![A::Int]},
-        InsDef { opcode: 551, alt_names: vec!["unknown551"], arg_format: vec![A::Int]},
-        InsDef { opcode: 552, alt_names: vec!["zIndex"], arg_format: vec![A::Int]},
-        InsDef { opcode: 553, alt_names: vec!["hitSound"], arg_format: vec![A::Int]},
-        InsDef { opcode: 554, alt_names: vec!["logo"], arg_format: vec![]},
-        InsDef { opcode: 555, alt_names: vec!["enmAlive"], arg_format: vec![A::IntRef, A::Int]},
-        InsDef { opcode: 556, alt_names: vec!["setDeath"], arg_format: vec![A::Str]},
-        InsDef { opcode: 557, alt_names: vec!["fogTime"], arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float]},
-        InsDef { opcode: 558, alt_names: vec!["flagMirror"], arg_format: vec![A::Int]},
-        InsDef { opcode: 559, alt_names: vec!["enmLimit"], arg_format: vec![A::Int]},
-        InsDef { opcode: 560, alt_names: vec!["setBounceRect"], arg_format: vec![A::Float, A::Float]},
-        InsDef { opcode: 561, alt_names: vec!["die"], arg_format: vec![]},
-        InsDef { opcode: 562, alt_names: vec!["dropItemsSp"], arg_format: vec![]},
-        InsDef { opcode: 563, alt_names: vec!["hbRect"], arg_format: vec![A::Int]},
-        InsDef { opcode: 564, alt_names: vec!["hitboxRotate"], arg_format: vec![A::Float]},
-        InsDef { opcode: 565, alt_names: vec!["bombInv"], arg_format: vec![A::Float]},
-        InsDef { opcode: 566, alt_names: vec!["unknown566"], arg_format: vec![]},
-        InsDef { opcode: 567, alt_names: vec!["unknown567"], arg_format: vec![A::Int]},
-        InsDef { opcode: 568, alt_names: vec!["spellMode"], arg_format: vec![A::Int]},
-        InsDef { opcode: 569, alt_names: vec!["unknown569"], arg_format: vec![A::Int]},
-        InsDef { opcode: 570, alt_names: vec!["unknown570"], arg_format: vec![]},
-        InsDef { opcode: 571, alt_names: vec!["unknown571"], arg_format: vec![]},
-        InsDef { opcode: 572, alt_names: vec!["lifeNow"], arg_format: vec![A::Int]},
+        InsDef { opcode: 500, alt_names: names(&["setHurtbox"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 501, alt_names: names(&["setHitbox"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 502, alt_names: names(&["flagSet"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 503, alt_names: names(&["flagClear"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 504, alt_names: names(&["moveLimit"]), arg_format: vec![A::Float, A::Float, A::Float, A::Float] },
+        InsDef { opcode: 505, alt_names: names(&["moveLimitReset"]), arg_format: vec![] },
+        InsDef { opcode: 506, alt_names: names(&["dropClear"]), arg_format: vec![] },
+        InsDef { opcode: 507, alt_names: names(&["dropExtra"]), arg_format: vec![A::Int, A::Int] },
+        InsDef { opcode: 508, alt_names: names(&["dropArea"]), arg_format: vec![A::Float, A::Float] },
+        InsDef { opcode: 509, alt_names: names(&["dropItems"]), arg_format: vec![] },
+        InsDef { opcode: 510, alt_names: names(&["dropMain"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 511, alt_names: names(&["lifeSet"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 512, alt_names: names(&["setBoss"]), arg_format: vec![A::Int] },
+        InsDef { opcode: 513, alt_names: names(&["timerReset"]), arg_format: vec![] },
+        InsDef { opcode: 514, alt_names: names(&["setInterrupt"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]},
+        InsDef { opcode: 515, alt_names: names(&["setInvuln"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 516, alt_names: names(&["playSound"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 517, alt_names: names(&["setScreenShake"]), arg_format: vec![A::Int, A::Int, A::Int]},
+        InsDef { opcode: 518, alt_names: names(&["dialogueRead"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 519, alt_names: names(&["dialogueWait"]), arg_format: vec![]},
+        InsDef { opcode: 520, alt_names: names(&["bossWait"]), arg_format: vec![]},
+        InsDef { opcode: 521, alt_names: names(&["setTimeout"]), arg_format: vec![A::Int, A::Str]},
+        InsDef { opcode: 522, alt_names: names(&["spellEx"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]},
+        InsDef { opcode: 523, alt_names: names(&["spellEnd"]), arg_format: vec![]},
+        InsDef { opcode: 524, alt_names: names(&["setChapter"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 525, alt_names: names(&["enmKillAll"]), arg_format: vec![]},
+        InsDef { opcode: 526, alt_names: names(&["etProtectRange"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 527, alt_names: names(&["lifeMarker"]), arg_format: vec![A::Int, A::Float, A::Int]},
+        InsDef { opcode: 528, alt_names: names(&["spellUnused"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
+        InsDef { opcode: 529, alt_names: names(&["rankF3"]), arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 530, alt_names: names(&["rankF5"]), arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 531, alt_names: names(&["rankF2"]), arg_format: vec![A::FloatRef, A::Float, A::Float]},
+        InsDef { opcode: 532, alt_names: names(&["rankI3"]), arg_format: vec![A::IntRef, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 533, alt_names: names(&["rankI5"]), arg_format: vec![A::IntRef, A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 534, alt_names: names(&["rankI2"]), arg_format: vec![A::IntRef, A::Int, A::Int]},
+        InsDef { opcode: 535, alt_names: names(&["diffI"]), arg_format: vec![A::IntRef, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 536, alt_names: names(&["diffF"]), arg_format: vec![A::FloatRef, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 537, alt_names: names(&["spell"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
+        InsDef { opcode: 538, alt_names: names(&["spell2"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
+        InsDef { opcode: 539, alt_names: names(&["spell3"]), arg_format: vec![A::Int, A::Int, A::Int, A::Str]}, // string needs to be encoded
+        InsDef { opcode: 540, alt_names: names(&["stars"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 541, alt_names: names(&["noHbDur"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 542, alt_names: names(&["spellTimeout"]), arg_format: vec![]},
+        InsDef { opcode: 543, alt_names: names(&["unknown543"]), arg_format: vec![]},
+        InsDef { opcode: 544, alt_names: names(&["unknown544"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 545, alt_names: names(&["laserCancel"]), arg_format: vec![]},
+        InsDef { opcode: 546, alt_names: names(&["bombShield"]), arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 547, alt_names: names(&["gameSpeed"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 548, alt_names: names(&["diffWait"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 549, alt_names: names(&["unknown549"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 550, alt_names: names(&["unknown550"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 551, alt_names: names(&["unknown551"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 552, alt_names: names(&["zIndex"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 553, alt_names: names(&["hitSound"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 554, alt_names: names(&["logo"]), arg_format: vec![]},
+        InsDef { opcode: 555, alt_names: names(&["enmAlive"]), arg_format: vec![A::IntRef, A::Int]},
+        InsDef { opcode: 556, alt_names: names(&["setDeath"]), arg_format: vec![A::Str]},
+        InsDef { opcode: 557, alt_names: names(&["fogTime"]), arg_format: vec![A::Int, A::Int, A::Int, A::Float, A::Float]},
+        InsDef { opcode: 558, alt_names: names(&["flagMirror"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 559, alt_names: names(&["enmLimit"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 560, alt_names: names(&["setBounceRect"]), arg_format: vec![A::Float, A::Float]},
+        InsDef { opcode: 561, alt_names: names(&["die"]), arg_format: vec![]},
+        InsDef { opcode: 562, alt_names: names(&["dropItemsSp"]), arg_format: vec![]},
+        InsDef { opcode: 563, alt_names: names(&["hbRect"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 564, alt_names: names(&["hitboxRotate"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 565, alt_names: names(&["bombInv"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 566, alt_names: names(&["unknown566"]), arg_format: vec![]},
+        InsDef { opcode: 567, alt_names: names(&["unknown567"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 568, alt_names: names(&["spellMode"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 569, alt_names: names(&["unknown569"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 570, alt_names: names(&["unknown570"]), arg_format: vec![]},
+        InsDef { opcode: 571, alt_names: names(&["unknown571"]), arg_format: vec![]},
+        InsDef { opcode: 572, alt_names: names(&["lifeNow"]), arg_format: vec![A::Int]},
 
-        InsDef { opcode: 600, alt_names: vec!["etNew"], arg_format: vec![A::Int]},
-        InsDef { opcode: 601, alt_names: vec!["etOn"], arg_format: vec![A::Int]},
-        InsDef { opcode: 602, alt_names: vec!["etSprite"], arg_format: vec![A::Int, A::Int, A::Int]},
-        InsDef { opcode: 603, alt_names: vec!["etOffset"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 604, alt_names: vec!["etAngle"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 605, alt_names: vec!["etSpeed"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 606, alt_names: vec!["etCount"], arg_format: vec![A::Int, A::Int, A::Int]},
-        InsDef { opcode: 607, alt_names: vec!["etAim"], arg_format: vec![A::Int, A::Int]},
-        InsDef { opcode: 608, alt_names: vec!["etSound"], arg_format: vec![A::Int, A::Int, A::Int]},
-        InsDef { opcode: 609, alt_names: vec!["etExSet"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float]},
-        InsDef { opcode: 610, alt_names: vec!["etExSet2"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 611, alt_names: vec!["etEx"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float]},
-        InsDef { opcode: 612, alt_names: vec!["etEx2"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 613, alt_names: vec!["etClearAll"], arg_format: vec![]},
-        InsDef { opcode: 614, alt_names: vec!["etCopy"], arg_format: vec![A::Int, A::Int]},
-        InsDef { opcode: 615, alt_names: vec!["etCancel"], arg_format: vec![A::Float]},
-        InsDef { opcode: 616, alt_names: vec!["etClear"], arg_format: vec![A::Float]},
-        InsDef { opcode: 617, alt_names: vec!["etSpeedR3"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 618, alt_names: vec!["etSpeedR5"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 619, alt_names: vec!["etSpeedR2"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 620, alt_names: vec!["etCountR3"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 621, alt_names: vec!["etCountR5"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 622, alt_names: vec!["etCountR2"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 623, alt_names: vec!["angleToPlayer"], arg_format: vec![A::FloatRef, A::Float, A::Float]},
-        InsDef { opcode: 624, alt_names: vec!["etSpeedD"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 625, alt_names: vec!["etCountD"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 626, alt_names: vec!["etOffsetRad"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 627, alt_names: vec!["etDist"], arg_format: vec![A::Int, A::Float]},
-        InsDef { opcode: 628, alt_names: vec!["etOrigin"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 629, alt_names: vec!["fog"], arg_format: vec![A::Float, A::Int]},
-        InsDef { opcode: 630, alt_names: vec!["callStd"], arg_format: vec![A::Int]},
-        InsDef { opcode: 631, alt_names: vec!["lifeHide"], arg_format: vec![A::Int]},
-        InsDef { opcode: 632, alt_names: vec!["funcSet"], arg_format: vec![A::Int]},
-        InsDef { opcode: 633, alt_names: vec!["flagExtDmg"], arg_format: vec![A::Int]},
-        InsDef { opcode: 634, alt_names: vec!["setHitboxFunc"], arg_format: vec![A::Int]},
-        InsDef { opcode: 635, alt_names: vec!["etCancelAsBomb"], arg_format: vec![A::Float]},
-        InsDef { opcode: 636, alt_names: vec!["etClearAsBomb"], arg_format: vec![A::Float]},
-        InsDef { opcode: 637, alt_names: vec!["funcCall"], arg_format: vec![A::Int]},
-        InsDef { opcode: 638, alt_names: vec!["scoreAdd"], arg_format: vec![A::Int]},
-        InsDef { opcode: 639, alt_names: vec!["funcSet2"], arg_format: vec![A::Int]},
-        InsDef { opcode: 640, alt_names: vec!["etExSub"], arg_format: vec![A::Int, A::Int, A::Str]},
-        InsDef { opcode: 641, alt_names: vec!["etExSubtract"], arg_format: vec![A::Int]},
+        InsDef { opcode: 600, alt_names: names(&["etNew"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 601, alt_names: names(&["etOn"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 602, alt_names: names(&["etSprite"]), arg_format: vec![A::Int, A::Int, A::Int]},
+        InsDef { opcode: 603, alt_names: names(&["etOffset"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 604, alt_names: names(&["etAngle"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 605, alt_names: names(&["etSpeed"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 606, alt_names: names(&["etCount"]), arg_format: vec![A::Int, A::Int, A::Int]},
+        InsDef { opcode: 607, alt_names: names(&["etAim"]), arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 608, alt_names: names(&["etSound"]), arg_format: vec![A::Int, A::Int, A::Int]},
+        InsDef { opcode: 609, alt_names: names(&["etExSet"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float]},
+        InsDef { opcode: 610, alt_names: names(&["etExSet2"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 611, alt_names: names(&["etEx"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float]},
+        InsDef { opcode: 612, alt_names: names(&["etEx2"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 613, alt_names: names(&["etClearAll"]), arg_format: vec![]},
+        InsDef { opcode: 614, alt_names: names(&["etCopy"]), arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 615, alt_names: names(&["etCancel"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 616, alt_names: names(&["etClear"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 617, alt_names: names(&["etSpeedR3"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 618, alt_names: names(&["etSpeedR5"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 619, alt_names: names(&["etSpeedR2"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 620, alt_names: names(&["etCountR3"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 621, alt_names: names(&["etCountR5"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 622, alt_names: names(&["etCountR2"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 623, alt_names: names(&["angleToPlayer"]), arg_format: vec![A::FloatRef, A::Float, A::Float]},
+        InsDef { opcode: 624, alt_names: names(&["etSpeedD"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 625, alt_names: names(&["etCountD"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 626, alt_names: names(&["etOffsetRad"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 627, alt_names: names(&["etDist"]), arg_format: vec![A::Int, A::Float]},
+        InsDef { opcode: 628, alt_names: names(&["etOrigin"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 629, alt_names: names(&["fog"]), arg_format: vec![A::Float, A::Int]},
+        InsDef { opcode: 630, alt_names: names(&["callStd"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 631, alt_names: names(&["lifeHide"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 632, alt_names: names(&["funcSet"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 633, alt_names: names(&["flagExtDmg"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 634, alt_names: names(&["setHitboxFunc"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 635, alt_names: names(&["etCancelAsBomb"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 636, alt_names: names(&["etClearAsBomb"]), arg_format: vec![A::Float]},
+        InsDef { opcode: 637, alt_names: names(&["funcCall"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 638, alt_names: names(&["scoreAdd"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 639, alt_names: names(&["funcSet2"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 640, alt_names: names(&["etExSub"]), arg_format: vec![A::Int, A::Int, A::Str]},
+        InsDef { opcode: 641, alt_names: names(&["etExSubtract"]), arg_format: vec![A::Int]},
 
-        InsDef { opcode: 700, alt_names: vec!["laserNew"], arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float]},
-        InsDef { opcode: 701, alt_names: vec!["laserTiming"], arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
-        InsDef { opcode: 702, alt_names: vec!["laserOn"], arg_format: vec![A::Int]},
-        InsDef { opcode: 703, alt_names: vec!["laserStOn"], arg_format: vec![A::Int, A::Int]},
-        InsDef { opcode: 704, alt_names: vec!["laserOffset"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 705, alt_names: vec!["laserTrajectory"], arg_format: vec![A::Int, A::Float, A::Float]},
-        InsDef { opcode: 706, alt_names: vec!["laserStLength"], arg_format: vec![A::Int, A::Float]},
-        InsDef { opcode: 707, alt_names: vec!["laserStWidth"], arg_format: vec![A::Int, A::Float]},
-        InsDef { opcode: 708, alt_names: vec!["laserStAngle"], arg_format: vec![A::Int, A::Float]},
-        InsDef { opcode: 709, alt_names: vec!["laserStRotation"], arg_format: vec![A::Int, A::Float]},
-        InsDef { opcode: 710, alt_names: vec!["laserStEnd"], arg_format: vec![A::Int]},
-        InsDef { opcode: 711, alt_names: vec!["laserCuOn"], arg_format: vec![A::Int]},
-        InsDef { opcode: 712, alt_names: vec!["etCancelRect"], arg_format: vec![A::Float, A::Float]},
-        InsDef { opcode: 713, alt_names: vec!["LaserBeOn"], arg_format: vec![A::Int, A::Int]},
-        InsDef { opcode: 714, alt_names: vec!["LaserBeCall"], arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 700, alt_names: names(&["laserNew"]), arg_format: vec![A::Int, A::Float, A::Float, A::Float, A::Float]},
+        InsDef { opcode: 701, alt_names: names(&["laserTiming"]), arg_format: vec![A::Int, A::Int, A::Int, A::Int, A::Int, A::Int]},
+        InsDef { opcode: 702, alt_names: names(&["laserOn"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 703, alt_names: names(&["laserStOn"]), arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 704, alt_names: names(&["laserOffset"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 705, alt_names: names(&["laserTrajectory"]), arg_format: vec![A::Int, A::Float, A::Float]},
+        InsDef { opcode: 706, alt_names: names(&["laserStLength"]), arg_format: vec![A::Int, A::Float]},
+        InsDef { opcode: 707, alt_names: names(&["laserStWidth"]), arg_format: vec![A::Int, A::Float]},
+        InsDef { opcode: 708, alt_names: names(&["laserStAngle"]), arg_format: vec![A::Int, A::Float]},
+        InsDef { opcode: 709, alt_names: names(&["laserStRotation"]), arg_format: vec![A::Int, A::Float]},
+        InsDef { opcode: 710, alt_names: names(&["laserStEnd"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 711, alt_names: names(&["laserCuOn"]), arg_format: vec![A::Int]},
+        InsDef { opcode: 712, alt_names: names(&["etCancelRect"]), arg_format: vec![A::Float, A::Float]},
+        InsDef { opcode: 713, alt_names: names(&["LaserBeOn"]), arg_format: vec![A::Int, A::Int]},
+        InsDef { opcode: 714, alt_names: names(&["LaserBeCall"]), arg_format: vec![A::Int, A::Int]},
 
-        InsDef { opcode: 800, alt_names: vec!["enmCall"], arg_format: vec![A::Int, A::Str]},
-        InsDef { opcode: 801, alt_names: vec!["enmPos"], arg_format: vec![A::FloatRef, A::FloatRef, A::Int]},
-        InsDef { opcode: 802, alt_names: vec!["broadcastInt"], arg_format: vec![A::Int]},
-    ];
-    v
-    };
+        InsDef { opcode: 800, alt_names: names(&["enmCall"]), arg_format: vec![A::Int, A::Str]},
+        InsDef { opcode: 801, alt_names: names(&["enmPos"]), arg_format: vec![A::FloatRef, A::FloatRef, A::Int]},
+        InsDef { opcode: 802, alt_names: names(&["broadcastInt"]), arg_format: vec![A::Int]},
+    ]
 }
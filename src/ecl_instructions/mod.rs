@@ -1,2 +1,4 @@
+mod eclmap;
 mod instructions;
+pub use eclmap::EclMap;
 pub use instructions::*;
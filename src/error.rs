@@ -1,29 +1,66 @@
-use std::ops::Range;
+use std::{cell::RefCell, ops::Range};
 
-use crate::lexer::Location;
+use crate::{
+    lexer::{Location, SourceFile, SourceMap},
+    lint::Lint,
+};
+
+pub use crate::error_code::ErrorCode;
 
 #[derive(Debug)]
 pub enum Error {
     IO(std::io::Error),
     Simple(String),
+    /// Operands or arguments of the wrong type, at the expression.
+    Type(String, Location),
     BackEnd(String),
     Grammar(String),
     ShouldNeverBeThere,
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IO(e) => write!(f, "{e}"),
+            Self::Simple(s) => write!(f, "{s}"),
+            Self::Type(s, _) => write!(f, "{s}"),
+            Self::BackEnd(s) => write!(f, "BackEnd error: {s}"),
+            Self::Grammar(s) => write!(f, "Grammar error: {s}"),
+            Self::ShouldNeverBeThere => {
+                write!(f, "An error occured that should have never occured")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// The code of the error. The errors of the source are reported where
+    /// they are found, a `Simple` one left unreported is a bug of eclc.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::IO(_) => ErrorCode::Io,
+            Self::Type(..) => ErrorCode::TypeMismatch,
+            Self::Grammar(_) => ErrorCode::GrammarFile,
+            Self::Simple(_) | Self::BackEnd(_) | Self::ShouldNeverBeThere => ErrorCode::Internal,
+        }
+    }
+}
+
 // error: expected item, found `:`
 //  --> src/grammar.rs:5:1
 //   |
 // 5 | :
 //   | ^ expected item
 
-pub struct ErrReport<'r> {
-    pub line: usize,
-    pub span: Range<usize>,
-    pub col: crossterm::style::Color,
-    pub msg: &'r str,
-    pub underline: char,
-    pub col_text: bool,
+struct ErrReport<'r> {
+    line: usize,
+    span: Range<usize>,
+    col: crossterm::style::Color,
+    msg: &'r str,
+    underline: char,
+    col_text: bool,
 }
 
 use crossterm::style::Stylize;
@@ -46,6 +83,13 @@ fn colorize_string_part(
 fn decorate_line(line: &str, reports: &[&ErrReport]) -> Vec<String> {
     // check for clashs.
     // right now each report is on its line.
+    let original = line;
+    // spans are in bytes, the underline is in characters
+    let width = |r: Range<usize>| {
+        original
+            .get(r.clone())
+            .map_or(r.len(), |s| s.chars().count())
+    };
     let mut line = line.to_string();
     let mut additionnal_lines = Vec::new();
     for r in reports {
@@ -53,14 +97,14 @@ fn decorate_line(line: &str, reports: &[&ErrReport]) -> Vec<String> {
             line = colorize_string_part(&line, r.span.clone(), r.col);
         }
         let mut add_line = String::new();
-        let mut squiglies = String::from(r.underline).repeat(r.span.len());
+        let mut squiglies = String::from(r.underline).repeat(width(r.span.clone()));
         squiglies.push(' ');
         if r.msg.len() > 200 {
             squiglies.push_str("Here");
         } else {
             squiglies.push_str(r.msg);
         }
-        add_line.push_str(&" ".repeat(r.span.start));
+        add_line.push_str(&" ".repeat(width(0..r.span.start)));
         add_line.push_str(&squiglies.with(r.col).bold().to_string());
         additionnal_lines.push(add_line);
     }
@@ -100,7 +144,284 @@ fn prefix_w_line_number(
     out
 }
 
-pub fn create_report_content(
+/// What the diagnostics say: errors stop the compilation, warnings don't,
+/// notes add information to the diagnostic before them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A message about the source, kept until the `Session` renders it.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Where the message points, if anywhere.
+    pub loc: Option<Location>,
+    /// Text under the pointed code.
+    pub label: String,
+    /// Shown right after the diagnostic: other places, near matches...
+    pub notes: Vec<Diagnostic>,
+    /// For an error, what `eclc --explain` explains.
+    pub code: Option<ErrorCode>,
+    /// For a warning, its kind, which sets its level.
+    pub lint: Option<Lint>,
+    /// What could replace the pointed code, like the signatures of the
+    /// instructions close to an unknown one.
+    pub suggestions: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: ErrorCode, loc: &Location, message: &str, label: &str) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.to_owned(),
+            loc: Some(loc.clone()),
+            label: label.to_owned(),
+            notes: vec![],
+            code: Some(code),
+            lint: None,
+            suggestions: vec![],
+        }
+    }
+
+    pub fn warning(lint: Lint, loc: &Location, message: &str, label: &str) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.to_owned(),
+            loc: Some(loc.clone()),
+            label: label.to_owned(),
+            notes: vec![],
+            code: None,
+            lint: Some(lint),
+            suggestions: vec![],
+        }
+    }
+
+    pub fn note(loc: &Location, message: &str) -> Self {
+        Self {
+            severity: Severity::Note,
+            message: message.to_owned(),
+            loc: Some(loc.clone()),
+            label: String::new(),
+            notes: vec![],
+            code: None,
+            lint: None,
+            suggestions: vec![],
+        }
+    }
+
+    /// A diagnostic that doesn't point at the source.
+    pub fn simple(severity: Severity, message: &str) -> Self {
+        Self {
+            severity,
+            message: message.to_owned(),
+            loc: None,
+            label: String::new(),
+            notes: vec![],
+            code: None,
+            lint: None,
+            suggestions: vec![],
+        }
+    }
+
+    /// The diagnostic of an error that wasn't reported where it happened.
+    pub fn from_error(e: &Error) -> Self {
+        if let Error::Type(s, loc) = e {
+            return Self::error(ErrorCode::TypeMismatch, loc, s, "");
+        }
+        let mut d = Self::simple(Severity::Error, &e.to_string());
+        d.code = Some(e.code());
+        d
+    }
+
+    pub fn with_note(mut self, note: Diagnostic) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    /// Terminal rendering, the located code taken from `sources`.
+    pub fn render(&self, sources: &SourceMap) -> String {
+        let (typ, col) = match self.severity {
+            Severity::Error => ("error", crossterm::style::Color::DarkRed),
+            Severity::Warning => ("warning", crossterm::style::Color::Yellow),
+            Severity::Note => ("note", crossterm::style::Color::Blue),
+        };
+        let typ = match self.code {
+            Some(c) => format!("{typ}[{c}]"),
+            None => typ.to_owned(),
+        };
+        // the located errors and warnings stand out, the located notes don't
+        let mut typ = typ.with(col);
+        let mut remain = format!(": {}", self.message).stylize();
+        if self.severity != Severity::Note || self.loc.is_none() {
+            typ = typ.bold();
+        }
+        if self.severity != Severity::Note && self.loc.is_some() {
+            remain = remain.bold();
+        }
+        let mut s = format!("{typ}{remain}\n");
+        let Some(loc) = &self.loc else {
+            for n in &self.notes {
+                s.push_str(&n.render(sources));
+            }
+            return s;
+        };
+        s.push_str(&format!(
+            "{}{}\n",
+            "  --> ".with(crossterm::style::Color::Blue).bold(),
+            sources.position(loc)
+        ));
+        let (underline, col_text) = match self.severity {
+            Severity::Error | Severity::Warning => ('^', false),
+            Severity::Note => ('~', true),
+        };
+        s.push_str(&create_report_content(
+            sources.get(loc.file),
+            loc.line..loc.line + 1,
+            vec![ErrReport {
+                line: loc.line,
+                span: loc.span.clone(),
+                col,
+                msg: &self.label,
+                underline,
+                col_text,
+            }],
+            !self.notes.is_empty(),
+        ));
+        for n in &self.notes {
+            s.push_str(&n.render(sources));
+        }
+        if self.notes.last().is_none_or(|n| n.loc.is_none()) {
+            s.push('\n');
+        }
+        s
+    }
+
+    /// Rendering for the tools reading the diagnostics: lines and columns
+    /// are 1-based, the columns counted in characters and the end excluded.
+    pub fn to_json(&self, sources: &SourceMap) -> serde_json::Value {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+        let (file, span) = match &self.loc {
+            Some(loc) => {
+                let cols = sources.columns(loc);
+                (
+                    Some(sources.get(loc.file).filename.as_str()),
+                    Some(serde_json::json!({
+                        "line_start": loc.line + 1,
+                        "column_start": cols.start,
+                        "line_end": loc.line + 1,
+                        "column_end": cols.end,
+                    })),
+                )
+            }
+            None => (None, None),
+        };
+        serde_json::json!({
+            "severity": severity,
+            "code": self.code.map(|c| c.code()).or(self.lint.map(|l| l.name().to_owned())),
+            "message": self.message,
+            "file": file,
+            "span": span,
+            "label": self.label,
+            "notes": self.notes.iter().map(|n| n.to_json(sources)).collect::<Vec<_>>(),
+            "suggestions": self.suggestions,
+        })
+    }
+}
+
+thread_local! {
+    /// The diagnostics of the `collect` calls running on this thread, the
+    /// innermost last. Nothing stays there once they return.
+    static COLLECTING: RefCell<Vec<Vec<Diagnostic>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Ends a `collect`, even when it unwinds.
+struct Collecting(usize);
+
+impl Drop for Collecting {
+    fn drop(&mut self) {
+        COLLECTING.with_borrow_mut(|c| c.truncate(self.0));
+    }
+}
+
+/// Runs `f` and returns the diagnostics it reported with its result, for
+/// the `Session` running it to keep them. Calls on several sessions, or
+/// nested ones, each get their own diagnostics.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Diagnostic>) {
+    let depth = COLLECTING.with_borrow_mut(|c| {
+        c.push(Vec::new());
+        c.len() - 1
+    });
+    let _collecting = Collecting(depth);
+    let value = f();
+    let diagnostics = COLLECTING.with_borrow_mut(|c| c.pop().unwrap_or_default());
+    (value, diagnostics)
+}
+
+/// Reports `d` to the innermost `collect`. Without one, nobody would read
+/// it and it is dropped.
+pub fn emit(d: Diagnostic) {
+    COLLECTING.with_borrow_mut(|c| {
+        if let Some(ds) = c.last_mut() {
+            ds.push(d);
+        }
+    });
+}
+
+fn error_count() -> usize {
+    COLLECTING.with_borrow(|c| {
+        c.last().map_or(0, |ds| {
+            ds.iter().filter(|d| d.severity == Severity::Error).count()
+        })
+    })
+}
+
+/// Goes on after the errors of steps that don't depend on each other,
+/// so that a run reports as many errors as it can.
+#[derive(Default)]
+pub struct Errors {
+    failed: bool,
+}
+
+impl Errors {
+    /// Runs `f`, reporting its error if it didn't report it itself.
+    pub fn check<T>(&mut self, f: impl FnOnce() -> Result<T, Error>) -> Option<T> {
+        let before = error_count();
+        match f() {
+            Ok(v) => Some(v),
+            Err(e) => {
+                if error_count() == before {
+                    emit(Diagnostic::from_error(&e));
+                }
+                self.failed = true;
+                None
+            }
+        }
+    }
+
+    /// For an error reported directly.
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+
+    /// `value`, if none of the steps failed.
+    pub fn finish<T>(self, value: T) -> Result<T, Error> {
+        if self.failed {
+            return Err(Error::Simple("errors were reported".to_owned()));
+        }
+        Ok(value)
+    }
+}
+
+fn create_report_content(
+    cf: &SourceFile,
     lines: Range<usize>,
     reports: Vec<ErrReport>,
     onemore: bool,
@@ -111,14 +432,12 @@ pub fn create_report_content(
     s.push_str(&"|".with(crossterm::style::Color::Blue).bold().to_string());
     s.push('\n');
     let om = s.clone();
-    let lock = crate::GLOBAL.lock().unwrap();
-    let cf = &lock.code_file.as_ref().unwrap();
     for i in lines {
         let line = cf.get_line(i);
         let reports_for_this_line: Vec<_> = reports.iter().filter(|r| r.line == i).collect();
         let mod_lines = decorate_line(line, &reports_for_this_line);
         let mod_lines =
-            prefix_w_line_number(i, size, &mod_lines, crossterm::style::Color::Blue, true);
+            prefix_w_line_number(i + 1, size, &mod_lines, crossterm::style::Color::Blue, true);
         for l in mod_lines {
             s.push_str(&l);
             s.push('\n');
@@ -130,94 +449,19 @@ pub fn create_report_content(
     s
 }
 
-pub fn report_message_header(
-    loc: &Location,
-    msg: &str,
-    typ: &str,
-    col: crossterm::style::Color,
-    bold: bool,
-) {
-    let lock = crate::GLOBAL.lock().unwrap();
-    let cfname = &lock.code_file.as_ref().unwrap().filename;
-    let mut typ = typ.with(col);
-    let mut remain = format!(": {}", msg).stylize();
-    if bold {
-        typ = typ.bold();
-        remain = remain.bold();
-    }
-    println!(
-        "{}{}\n{}{}:{}",
-        typ,
-        remain,
-        "  --> ".with(crossterm::style::Color::Blue).bold(),
-        cfname,
-        loc
-    );
-}
-
-pub fn report_error_ext_one_more(loc: &Location, text: &str, under_text: &str) {
-    report_message_header(loc, text, "error", crossterm::style::Color::DarkRed, true);
-    let report_content = create_report_content(
-        loc.line..loc.line + 1,
-        vec![ErrReport {
-            line: loc.line,
-            span: loc.span.clone(),
-            col: crossterm::style::Color::DarkRed,
-            msg: under_text,
-            underline: '^',
-            col_text: false,
-        }],
-        true,
-    );
-    print!("{}", report_content);
-}
-
-pub fn report_error_ext(loc: &Location, text: &str, under_text: &str) {
-    report_message_header(loc, text, "error", crossterm::style::Color::DarkRed, true);
-    let report_content = create_report_content(
-        loc.line..loc.line + 1,
-        vec![ErrReport {
-            line: loc.line,
-            span: loc.span.clone(),
-            col: crossterm::style::Color::DarkRed,
-            msg: under_text,
-            underline: '^',
-            col_text: false,
-        }],
-        false,
-    );
-    println!("{}", report_content);
-}
-
-pub fn report_error(loc: &Location, text: &str) {
-    report_message_header(loc, text, "error", crossterm::style::Color::DarkRed, true);
-    let report_content = create_report_content(
-        loc.line..loc.line + 1,
-        vec![ErrReport {
-            line: loc.line,
-            span: loc.span.clone(),
-            col: crossterm::style::Color::DarkRed,
-            msg: "",
-            underline: '^',
-            col_text: false,
-        }],
-        false,
-    );
-    println!("{}", report_content);
+pub fn report_error_ext(code: ErrorCode, loc: &Location, text: &str, under_text: &str) {
+    emit(Diagnostic::error(code, loc, text, under_text));
+}
+
+/// Reports a warning of kind `lint`, which the `Session` can allow or deny.
+pub fn warn(lint: Lint, loc: &Location, text: &str, under_text: &str) {
+    emit(Diagnostic::warning(lint, loc, text, under_text));
+}
+
+pub fn report_error(code: ErrorCode, loc: &Location, text: &str) {
+    emit(Diagnostic::error(code, loc, text, ""));
 }
 
 pub fn report_error_simple(text: &str) {
-    println!(
-        "{}: {}",
-        "error".bold().with(crossterm::style::Color::DarkRed),
-        text
-    );
-}
-
-pub fn report_note_simple(text: &str) {
-    println!(
-        "{}: {}",
-        "note".bold().with(crossterm::style::Color::Blue),
-        text
-    );
+    emit(Diagnostic::simple(Severity::Error, text));
 }
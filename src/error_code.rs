@@ -0,0 +1,98 @@
+//! The stable codes of the errors, shown as `error[E0007]` and explained
+//! by `eclc --explain E0007`. A code is never reused for another error.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum ErrorCode {
+    UnknownToken = 1,
+    Syntax = 2,
+    InvalidEscape = 3,
+    CharLiteral = 4,
+    Include = 5,
+    IncludeCycle = 6,
+    UnknownInstruction = 7,
+    VariableExists = 8,
+    UnknownVariable = 9,
+    UnresolvedIdentifier = 10,
+    NonIntCondition = 11,
+    NotConstant = 12,
+    ConstantType = 13,
+    AlreadyDefined = 14,
+    Unencodable = 15,
+    TypeMismatch = 16,
+    Io = 17,
+    GrammarFile = 18,
+    Internal = 19,
+    IntLiteral = 20,
+    NegativeTime = 21,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 21] = [
+        ErrorCode::UnknownToken,
+        ErrorCode::Syntax,
+        ErrorCode::InvalidEscape,
+        ErrorCode::CharLiteral,
+        ErrorCode::Include,
+        ErrorCode::IncludeCycle,
+        ErrorCode::UnknownInstruction,
+        ErrorCode::VariableExists,
+        ErrorCode::UnknownVariable,
+        ErrorCode::UnresolvedIdentifier,
+        ErrorCode::NonIntCondition,
+        ErrorCode::NotConstant,
+        ErrorCode::ConstantType,
+        ErrorCode::AlreadyDefined,
+        ErrorCode::Unencodable,
+        ErrorCode::TypeMismatch,
+        ErrorCode::Io,
+        ErrorCode::GrammarFile,
+        ErrorCode::Internal,
+        ErrorCode::IntLiteral,
+        ErrorCode::NegativeTime,
+    ];
+
+    /// `E0001`, `E0002`..., the number given to the variant.
+    pub fn code(self) -> String {
+        format!("E{:04}", self as u16)
+    }
+
+    /// The error of `code`, written `E0007`, `e0007` or `7`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let n = code.trim_start_matches(['E', 'e']).parse::<u16>().ok()?;
+        Self::ALL.into_iter().find(|c| *c as u16 == n)
+    }
+
+    /// What the error means, with an example and its fix.
+    pub fn explanation(self) -> &'static str {
+        match self {
+            ErrorCode::UnknownToken => include_str!("error_code/E0001.md"),
+            ErrorCode::Syntax => include_str!("error_code/E0002.md"),
+            ErrorCode::InvalidEscape => include_str!("error_code/E0003.md"),
+            ErrorCode::CharLiteral => include_str!("error_code/E0004.md"),
+            ErrorCode::Include => include_str!("error_code/E0005.md"),
+            ErrorCode::IncludeCycle => include_str!("error_code/E0006.md"),
+            ErrorCode::UnknownInstruction => include_str!("error_code/E0007.md"),
+            ErrorCode::VariableExists => include_str!("error_code/E0008.md"),
+            ErrorCode::UnknownVariable => include_str!("error_code/E0009.md"),
+            ErrorCode::UnresolvedIdentifier => include_str!("error_code/E0010.md"),
+            ErrorCode::NonIntCondition => include_str!("error_code/E0011.md"),
+            ErrorCode::NotConstant => include_str!("error_code/E0012.md"),
+            ErrorCode::ConstantType => include_str!("error_code/E0013.md"),
+            ErrorCode::AlreadyDefined => include_str!("error_code/E0014.md"),
+            ErrorCode::Unencodable => include_str!("error_code/E0015.md"),
+            ErrorCode::TypeMismatch => include_str!("error_code/E0016.md"),
+            ErrorCode::Io => include_str!("error_code/E0017.md"),
+            ErrorCode::GrammarFile => include_str!("error_code/E0018.md"),
+            ErrorCode::Internal => include_str!("error_code/E0019.md"),
+            ErrorCode::IntLiteral => include_str!("error_code/E0020.md"),
+            ErrorCode::NegativeTime => include_str!("error_code/E0021.md"),
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.code())
+    }
+}
@@ -1,4 +1,5 @@
 mod ll1_check;
+pub mod micro_lang;
 mod production_table;
 pub mod rules;
 mod symbol;
@@ -12,6 +13,7 @@ pub use rules::Rule;
 pub use symbol::Symbol;
 pub use symbol::SymbolName;
 
+use micro_lang::AstDef;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
@@ -21,23 +23,31 @@ pub struct Grammar {
     non_term: Vec<String>,
     first_sets: HashMap<String, HashSet<Symbol>>,
     follow_sets: HashMap<String, HashSet<Symbol>>,
-    ast_prod: HashMap<String, crate::parser::ast::AstDef>,
+    ast_prod: HashMap<String, AstDef>,
 }
 
 impl Grammar {
-    pub fn _gen_rust(&self) -> Result<(), std::io::Error> {
-        for (k, v) in &self.ast_prod {
-            println!("ast_prod.insert(\"{}\", {:?});", k, v);
-        }
+    /// Writes `production_table()` and `ast_prod()`, the parser of the
+    /// grammar, as Rust source. Used by build.rs.
+    #[allow(dead_code)]
+    pub fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
         let production_table = self.fill_ll1_production_table();
-        let f = std::fs::File::create("test.rs")?;
-        let mut buf = std::io::BufWriter::new(f);
-        production_table::_gen_rust_for_production_table(&mut buf, &production_table)?;
-        buf.flush()?;
-        Ok(())
+        production_table::gen_rust_for_production_table(buf, &production_table)?;
+
+        let mut prods: Vec<_> = self.ast_prod.iter().collect();
+        prods.sort_by_key(|(k, _)| *k);
+        writeln!(buf, "pub fn ast_prod() -> HashMap<String, AstDef> {{")?;
+        writeln!(buf, "    HashMap::from([")?;
+        for (k, v) in prods {
+            write!(buf, "        ({:?}.to_owned(), ", k)?;
+            v.gen_rust(buf)?;
+            writeln!(buf, "),")?;
+        }
+        writeln!(buf, "    ])")?;
+        writeln!(buf, "}}")
     }
 
-    pub fn get_ast_prod(&self) -> HashMap<String, crate::parser::ast::AstDef> {
+    pub fn get_ast_prod(&self) -> HashMap<String, AstDef> {
         self.ast_prod.clone()
     }
 
@@ -222,7 +232,7 @@ impl Grammar {
             let non_terminal = &rule.left;
 
             // Calculate the FIRST set for the right-hand side of the rule.
-            let mut first_set = self.getfirst(&rule.right).unwrap_or(HashSet::new());
+            let mut first_set = self.getfirst(&rule.right).unwrap_or_default();
 
             // If the rule is nullable, add the FOLLOW set for the non-terminal.
             if self.can_derive_epsilon(&rule.right) {
@@ -1,13 +1,14 @@
-use crate::error::Error;
-
-use super::{AstResolver, NeededForAstNode, Node};
+use std::io::Write;
 
+/// Action of a grammar rule, between braces: how the AST node of the rule
+/// is built from the nodes of its children.
 #[derive(Debug, Clone)]
 pub enum AstDef {
     Der(Der),
     Fun(Vec<String>, Vec<Der>),
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone)]
 pub enum Der {
     Child(usize),
@@ -15,42 +16,43 @@ pub enum Der {
     Param(usize),
 }
 
-impl Der {
-    pub fn execute<N: NeededForAstNode>(
-        &self,
-        resolver: &AstResolver<N>,
-        children: &[Node],
-        params: &[N],
-    ) -> Result<N, Error> {
+impl AstDef {
+    /// Rust expression building `self`, for the parser generated by build.rs.
+    pub fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
         match self {
-            Self::Child(n) => resolver.resolve(&children[*n], &[]),
-            Self::ChildDer(n, par) => {
-                let tmp: Result<Vec<N>, Error> = par
-                    .iter()
-                    .map(|p| p.execute(resolver, children, params))
-                    .collect();
-                resolver.resolve(&children[*n], &tmp?)
+            Self::Der(d) => {
+                write!(buf, "AstDef::Der(")?;
+                d.gen_rust(buf)?;
+            }
+            Self::Fun(name, params) => {
+                write!(buf, "AstDef::Fun(vec![")?;
+                for n in name {
+                    write!(buf, "{:?}.to_owned(), ", n)?;
+                }
+                write!(buf, "], vec![")?;
+                for p in params {
+                    p.gen_rust(buf)?;
+                    write!(buf, ", ")?;
+                }
+                write!(buf, "]")?;
             }
-            Self::Param(n) => Ok(params[*n].clone()),
         }
+        write!(buf, ")")
     }
 }
 
-impl AstDef {
-    pub fn execute<N: NeededForAstNode>(
-        &self,
-        resolver: &AstResolver<N>,
-        children: &[Node],
-        params: &[N],
-    ) -> Result<N, Error> {
+impl Der {
+    fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
         match self {
-            Self::Der(d) => d.execute(resolver, children, params),
-            Self::Fun(fun_name, par) => {
-                let par: Result<Vec<N>, Error> = par
-                    .iter()
-                    .map(|p| p.execute(resolver, children, params))
-                    .collect();
-                resolver.executor.exec(fun_name, &par?)
+            Self::Child(n) => write!(buf, "Der::Child({n})"),
+            Self::Param(n) => write!(buf, "Der::Param({n})"),
+            Self::ChildDer(n, params) => {
+                write!(buf, "Der::ChildDer({n}, vec![")?;
+                for p in params {
+                    p.gen_rust(buf)?;
+                    write!(buf, ", ")?;
+                }
+                write!(buf, "])")
             }
         }
     }
@@ -17,19 +17,19 @@ impl ProductionTableEntry {
         }
     }
 
-    fn _gen_rust<W: Write>(&self, buf: &mut BufWriter<W>) -> Result<(), std::io::Error> {
-        writeln!(buf, "    table.push(ProductionTableEntry::new(")?;
-        writeln!(buf, "        \"{}\",", self.nt)?;
-        writeln!(buf, "        \"{}\",", self.tok)?;
-        write!(buf, "        vec![")?;
+    fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
+        writeln!(buf, "        ProductionTableEntry::new(")?;
+        writeln!(buf, "            {:?},", self.nt)?;
+        writeln!(buf, "            {:?},", self.tok)?;
+        write!(buf, "            vec![")?;
         for (i, s) in self.produces.iter().enumerate() {
-            s._gen_rust(buf)?;
+            s.gen_rust(buf)?;
             if i < self.produces.len() - 1 {
                 write!(buf, ", ")?;
             }
         }
-        writeln!(buf, "]")?;
-        writeln!(buf, "    ));")?;
+        writeln!(buf, "],")?;
+        writeln!(buf, "        ),")?;
         Ok(())
     }
 }
@@ -61,17 +61,19 @@ pub fn get_production_table_tokens_for_nt<'a>(
         .collect()
 }
 
-use std::io::BufWriter;
-pub fn _gen_rust_for_production_table<W: Write>(
-    buf: &mut BufWriter<W>,
+pub fn gen_rust_for_production_table<W: Write>(
+    buf: &mut W,
     table: &ProductionTable,
 ) -> Result<(), std::io::Error> {
-    writeln!(buf, "pub fn gen_table() -> ProductionTable {{")?;
-    writeln!(buf, "    let mut table = vec![];")?;
-    for e in table {
-        e._gen_rust(buf)?;
+    // sorted, so that the same grammar always generates the same code
+    let mut entries: Vec<_> = table.iter().collect();
+    entries.sort_by(|a, b| (&a.nt, &a.tok).cmp(&(&b.nt, &b.tok)));
+    writeln!(buf, "pub fn production_table() -> ProductionTable {{")?;
+    writeln!(buf, "    vec![")?;
+    for e in entries {
+        e.gen_rust(buf)?;
     }
-    writeln!(buf, "    table")?;
+    writeln!(buf, "    ]")?;
     writeln!(buf, "}}")?;
     Ok(())
 }
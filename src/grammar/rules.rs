@@ -1,10 +1,10 @@
-use super::symbol::Symbol;
+use super::{micro_lang::AstDef, symbol::Symbol};
 
 pub struct RuleStrings {
     pub left: String,
     pub right: Vec<String>,
-    pub prio: crate::grammar_file::RulePriorities,
-    pub ast: crate::parser::ast::AstDef,
+    pub _prio: crate::grammar_file::RulePriorities,
+    pub ast: AstDef,
 }
 
 impl RuleStrings {
@@ -17,14 +17,13 @@ impl SymbolName for Symbol {
     }
 }
 
-use std::io::BufWriter;
 use std::io::Write;
 
 impl Symbol {
-    pub fn _gen_rust<W: Write>(&self, buf: &mut BufWriter<W>) -> Result<(), std::io::Error> {
+    pub fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
         match self {
-            Symbol::T(name) => write!(buf, "Symbol::T(\"{}\".to_owned())", name),
-            Symbol::NT(name) => write!(buf, "Symbol::NT(\"{}\".to_owned())", name),
+            Symbol::T(name) => write!(buf, "Symbol::T({:?}.to_owned())", name),
+            Symbol::NT(name) => write!(buf, "Symbol::NT({:?}.to_owned())", name),
         }
     }
 }
@@ -3,6 +3,7 @@ mod parse_rules;
 pub use parse_rules::parse_rules;
 
 use std::collections::HashMap;
+use std::io::Write;
 
 pub type RulePriorities = HashMap<String, i32>;
 
@@ -21,6 +22,18 @@ pub struct TokenDecl {
     escape: bool,
 }
 
+/// A call to the `LexerBuilder`.
+pub enum LexerRule<'a> {
+    Token {
+        regex: &'a str,
+        kind: &'a str,
+        escape: bool,
+    },
+    Eof(&'a str),
+    Error(&'a str),
+    Ignore(&'a str),
+}
+
 // todo: macros
 
 impl TokenDecl {
@@ -101,33 +114,30 @@ impl GrammarFile {
         self.next_line_prio.insert(tok.to_owned(), prio);
     }
 
-    pub fn _gen_rust(&self) {
-        println!("pub fn init_lexer<'a>() -> Lexer<&'a str> {{");
-        println!("    Lexer::builder()");
-        for i in &self.ignore_decls {
-            println!(
-                "        .ignore(\"{}\")",
-                &i[..].replace('\\', "\\\\").replace('"', "\\\"")
-            );
-        }
-        for t in &self.tokens {
-            if t.is_eof {
-                println!("        .eof(\"{}\")", &t.in_file[..]);
-                continue;
+    /// Writes `lexer()`, the lexer of the grammar, as Rust source. Used by build.rs.
+    #[allow(dead_code)]
+    pub fn gen_rust<W: Write>(&self, buf: &mut W) -> Result<(), std::io::Error> {
+        writeln!(buf, "pub fn lexer() -> Lexer<String> {{")?;
+        writeln!(buf, "    Lexer::builder()")?;
+        for r in self.lexer_rules() {
+            match r {
+                LexerRule::Token {
+                    regex,
+                    kind,
+                    escape,
+                } => writeln!(
+                    buf,
+                    "        .token({:?}, {:?}.to_owned(), {})",
+                    regex, kind, escape
+                )?,
+                LexerRule::Eof(kind) => writeln!(buf, "        .eof({:?}.to_owned())", kind)?,
+                LexerRule::Error(kind) => writeln!(buf, "        .error({:?}.to_owned())", kind)?,
+                LexerRule::Ignore(regex) => writeln!(buf, "        .ignore({:?})", regex)?,
             }
-            let re = match &t.regex {
-                Some(re) => re,
-                None => &t.in_file,
-            };
-            println!(
-                "        .token(\"{}\", \"{}\", {})",
-                &re.replace('\\', "\\\\").replace('"', "\\\""),
-                &t.in_file[..].replace('\\', "\\\\").replace('"', "\\\""),
-                t.escape
-            );
         }
-        println!("        .build().unwrap()");
-        println!("}}");
+        writeln!(buf, "        .build()")?;
+        writeln!(buf, "        .expect(\"Incorrect lexer definition\")")?;
+        writeln!(buf, "}}")
     }
 
     pub fn from_file(filename: &str) -> Result<Self, std::io::Error> {
@@ -251,23 +261,28 @@ impl GrammarFile {
         }
     }
 
-    pub fn lexer(&self) -> crate::lexer::Lexer<&str> {
-        let mut lb = crate::lexer::Lexer::builder();
+    /// The calls to make to the `LexerBuilder` for the tokens of the grammar.
+    pub fn lexer_rules(&self) -> Vec<LexerRule<'_>> {
+        let mut rules = Vec::new();
         for t in &self.tokens {
             if t.is_eof {
-                lb = lb.eof(&t.in_file[..]);
+                rules.push(LexerRule::Eof(&t.in_file));
                 continue;
             }
             let re = match &t.regex {
                 Some(re) => re,
                 None => &t.in_file,
             };
-            lb = lb.token(re, &t.in_file[..], t.escape);
+            rules.push(LexerRule::Token {
+                regex: re,
+                kind: &t.in_file,
+                escape: t.escape,
+            });
         }
-        lb = lb.error("ERROR");
+        rules.push(LexerRule::Error("ERROR"));
         for i in &self.ignore_decls {
-            lb = lb.ignore(&i[..]);
+            rules.push(LexerRule::Ignore(i));
         }
-        lb.build().expect("Incorrect lexer definition")
+        rules
     }
 }
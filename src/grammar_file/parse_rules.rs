@@ -1,5 +1,5 @@
 use super::{GrammarFile, RulePriorities};
-use crate::grammar::rules::RuleStrings;
+use crate::grammar::{micro_lang::parse_ast_def, rules::RuleStrings};
 
 fn parse_rule(body: &str, nt: &str, prio: RulePriorities, ast: &str) -> RuleStrings {
     let right = body
@@ -10,8 +10,8 @@ fn parse_rule(body: &str, nt: &str, prio: RulePriorities, ast: &str) -> RuleStri
     RuleStrings {
         left: nt.to_owned(),
         right,
-        prio,
-        ast: crate::parser::ast::parse_ast_def(ast),
+        _prio: prio,
+        ast: parse_ast_def(ast),
     }
 }
 
@@ -11,13 +11,13 @@ pub struct LexerBuilder<'r, K> {
     error: Option<K>,
 }
 
-impl<'r, K: Copy> Default for LexerBuilder<'r, K> {
+impl<'r, K> Default for LexerBuilder<'r, K> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'r, K: Copy> LexerBuilder<'r, K> {
+impl<'r, K> LexerBuilder<'r, K> {
     pub fn new() -> Self {
         LexerBuilder {
             regexes: Vec::new(),
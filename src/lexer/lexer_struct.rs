@@ -12,7 +12,7 @@ pub struct Lexer<K> {
     error: K,
 }
 
-impl<K: Copy> Lexer<K> {
+impl<K> Lexer<K> {
     pub fn new(
         kinds: Vec<Option<K>>,
         regexes: Vec<Regex>,
@@ -29,11 +29,18 @@ impl<K: Copy> Lexer<K> {
         }
     }
 
-    pub fn eof_token(&self) -> Option<K> {
-        self.eof
+    pub fn builder<'r>() -> LexerBuilder<'r, K> {
+        LexerBuilder::new()
+    }
+}
+
+/// The kinds of the tokens are borrowed from the lexer, which can own them.
+impl<K: AsRef<str>> Lexer<K> {
+    pub fn eof_token(&self) -> Option<&str> {
+        self.eof.as_ref().map(AsRef::as_ref)
     }
-    pub fn error(&self) -> K {
-        self.error.clone()
+    pub fn error(&self) -> &str {
+        self.error.as_ref()
     }
     pub fn get_regexes(&self) -> &Vec<Regex> {
         &self.regexes
@@ -41,12 +48,8 @@ impl<K: Copy> Lexer<K> {
     pub fn get_regex_set(&self) -> &RegexSet {
         &self.regex_set
     }
-    pub fn kind(&self, i: usize) -> Option<K> {
-        self.kinds[i]
-    }
-
-    pub fn builder<'r>() -> LexerBuilder<'r, K> {
-        LexerBuilder::new()
+    pub fn kind(&self, i: usize) -> Option<&str> {
+        self.kinds[i].as_ref().map(AsRef::as_ref)
     }
 
     pub fn tokens<'l>(&'l self, source: &SourceFile) -> Tokens<'l, K> {
@@ -1,6 +1,7 @@
 mod builder;
 mod lexer_struct;
 mod source_file;
+mod source_map;
 mod token;
 mod tokens;
 
@@ -8,7 +9,6 @@ pub use builder::LexerBuilder;
 pub use lexer_struct::Lexer;
 pub use source_file::Location;
 pub use source_file::SourceFile;
-pub use token::Token;
+pub use source_map::{FileId, SourceMap};
+pub use token::{Token, Trivia};
 pub use tokens::Tokens;
-
-pub use regex::Error;
@@ -1,17 +1,24 @@
+use std::io::Read;
+
+use super::FileId;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Location {
+    /// Id of the `SourceFile` the location is in.
+    pub file: FileId,
     pub line: usize,
     pub span: std::ops::Range<usize>,
 }
 
 impl Location {
     pub fn merge(&self, l2: &Location) -> Self {
-        if self.line != l2.line {
+        if self.file != l2.file || self.line != l2.line {
             self.clone()
         } else {
             let min = self.span.start.min(l2.span.start);
             let max = self.span.end.max(l2.span.end);
             Location {
+                file: self.file,
                 line: self.line,
                 span: min..max,
             }
@@ -27,6 +34,8 @@ impl std::fmt::Display for Location {
 
 #[derive(Debug, Clone)]
 pub struct SourceFile {
+    /// Set when the file is added to the `SourceMap`.
+    pub id: FileId,
     pub filename: String,
     pub content: String,
     line_sizes: Vec<usize>,
@@ -37,15 +46,32 @@ impl SourceFile {
         content.split('\n').map(|s| s.len() + 1).collect()
     }
 
+    /// Reads the file `filename`, or the standard input for `-`.
     pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+        if filename == "-" {
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)?;
+            return Ok(Self::from_string("<stdin>", content));
+        }
         let content = std::fs::read_to_string(filename)?;
         Ok(Self {
+            id: FileId::default(),
             filename: filename.to_string(),
             line_sizes: Self::calculate_line_sizes(&content),
             content,
         })
     }
 
+    /// Source that doesn't come from a file on disk.
+    pub fn from_string(filename: &str, content: String) -> Self {
+        Self {
+            id: FileId::default(),
+            filename: filename.to_string(),
+            line_sizes: Self::calculate_line_sizes(&content),
+            content,
+        }
+    }
+
     pub fn remaining(&self, pos: usize) -> &str {
         &self.content[pos..]
     }
@@ -58,6 +84,10 @@ impl SourceFile {
         self.content.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
     pub fn range_to_location(&self, range: std::ops::Range<usize>) -> Location {
         let len = range.end - range.start;
         let mut pos = range.start;
@@ -71,6 +101,7 @@ impl SourceFile {
             }
         }
         Location {
+            file: self.id,
             line,
             span: pos..pos + len,
         }
@@ -89,6 +120,7 @@ impl SourceFile {
 impl From<&str> for SourceFile {
     fn from(s: &str) -> SourceFile {
         SourceFile {
+            id: FileId::default(),
             filename: "dummy".to_owned(),
             content: s.to_owned(),
             line_sizes: Self::calculate_line_sizes(s),
@@ -0,0 +1,39 @@
+use super::{Location, SourceFile};
+
+/// Id of a file of the `SourceMap`, carried by every `Location`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub struct FileId(pub usize);
+
+/// Every source read during a run, so that the diagnostics can show
+/// the file of a `Location`.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// Adds `src`, giving it its id.
+    pub fn add(&mut self, mut src: SourceFile) -> SourceFile {
+        src.id = FileId(self.files.len());
+        self.files.push(src.clone());
+        src
+    }
+
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0]
+    }
+
+    /// `path:line:col` of `loc`, 1-based, the column counted in characters.
+    pub fn position(&self, loc: &Location) -> String {
+        let file = self.get(loc.file);
+        let col = self.columns(loc).start;
+        format!("{}:{}:{}", file.filename, loc.line + 1, col)
+    }
+
+    /// Columns of the span of `loc` on its line, 1-based and counted in characters.
+    pub fn columns(&self, loc: &Location) -> std::ops::Range<usize> {
+        let line = self.get(loc.file).get_line(loc.line);
+        let col = |i: usize| line.get(..i).map_or(i, |s| s.chars().count()) + 1;
+        col(loc.span.start)..col(loc.span.end)
+    }
+}
@@ -5,6 +5,26 @@ pub struct Token<K> {
     pub kind: K,
     pub loc: Location,
     pub text: String,
+    /// What the lexer ignored before the token, from the end of the
+    /// trailing trivia of the previous one. Only kept with
+    /// [`Tokens::with_trivia`](super::Tokens::with_trivia).
+    pub leading: Vec<Trivia>,
+    /// What the lexer ignored after the token on its line, up to the first
+    /// newline included.
+    pub trailing: Vec<Trivia>,
+}
+
+/// Text matched by an `!ignore` rule of the grammar: whitespace or a comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trivia {
+    pub loc: Location,
+    pub text: String,
+}
+
+impl Trivia {
+    pub fn is_comment(&self) -> bool {
+        !self.text.trim().is_empty()
+    }
 }
 
 impl<K: std::fmt::Display> std::fmt::Display for Token<K> {
@@ -1,8 +1,8 @@
-use crate::error::report_error;
+use crate::error::{report_error, ErrorCode};
 
 use super::Lexer;
 use super::SourceFile;
-use super::Token;
+use super::{Token, Trivia};
 
 #[derive(Debug)]
 pub struct Tokens<'l, K> {
@@ -10,6 +10,10 @@ pub struct Tokens<'l, K> {
     source: SourceFile,
     position: usize,
     eof: bool,
+    /// Whether the ignored text is kept as the trivia of the tokens.
+    trivia: bool,
+    /// Trivia read since the last token, leading the next one.
+    leading: Vec<Trivia>,
 }
 
 impl<'l, K> Tokens<'l, K> {
@@ -19,14 +23,77 @@ impl<'l, K> Tokens<'l, K> {
             source: source.clone(),
             position: 0,
             eof: false,
+            trivia: false,
+            leading: vec![],
         }
     }
+
+    /// Keeps the comments and the whitespace the grammar ignores in the
+    /// `leading` and `trailing` trivia of the tokens, for the tools which
+    /// give the source back.
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = true;
+        self
+    }
 }
 
-impl<'l, K: Copy> Iterator for Tokens<'l, K> {
-    type Item = Token<K>;
+impl<'l, K: AsRef<str>> Tokens<'l, K> {
+    /// Length and rule of what the lexer matches at its position.
+    fn matched(&self) -> Option<(usize, usize)> {
+        let string = self.source.remaining(self.position);
+        let match_set = self.lexer.get_regex_set().matches(string);
+        match_set
+            .into_iter()
+            .map(|i: usize| {
+                let m = self.lexer.get_regexes()[i].find(string).unwrap();
+                assert!(m.start() == 0);
+                (m.end(), i)
+            })
+            .next_back()
+    }
 
-    fn next(&mut self) -> Option<Token<K>> {
+    /// Reads `len` bytes as trivia.
+    fn read_trivia(&mut self, len: usize) -> Trivia {
+        let range = self.position..self.position + len;
+        self.position += len;
+        Trivia {
+            loc: self.source.range_to_location(range.clone()),
+            text: self.source.span(range).to_owned(),
+        }
+    }
+
+    /// Reads the trivia after a token ending on `line`, up to the end of the line.
+    fn trailing(&mut self, line: usize) -> Vec<Trivia> {
+        let mut trailing = Vec::new();
+        while self.position < self.source.len() {
+            let Some((len, i)) = self.matched() else {
+                break;
+            };
+            let loc = self.source.range_to_location(self.position..self.position);
+            if self.lexer.kind(i).is_some() || loc.line != line {
+                break;
+            }
+            // whitespace over several lines only trails up to its first newline
+            let text = self.source.span(self.position..self.position + len);
+            let len = match text.find('\n') {
+                Some(n) if text.trim().is_empty() => n + 1,
+                _ => len,
+            };
+            let t = self.read_trivia(len);
+            let newline = t.text.contains('\n');
+            trailing.push(t);
+            if newline {
+                break;
+            }
+        }
+        trailing
+    }
+}
+
+impl<'l, K: AsRef<str>> Iterator for Tokens<'l, K> {
+    type Item = Token<&'l str>;
+
+    fn next(&mut self) -> Option<Token<&'l str>> {
         loop {
             if self.eof {
                 return None;
@@ -37,46 +104,64 @@ impl<'l, K: Copy> Iterator for Tokens<'l, K> {
                     kind: self.lexer.eof_token()?,
                     loc: self.source.range_to_location(self.position..self.position),
                     text: "".to_owned(),
+                    leading: std::mem::take(&mut self.leading),
+                    trailing: vec![],
                 });
             }
 
             let string = self.source.remaining(self.position);
-            let match_set = self.lexer.get_regex_set().matches(string);
-            let result = match_set
-                .into_iter()
-                .map(|i: usize| {
-                    let m = self.lexer.get_regexes()[i].find(string).unwrap();
-                    assert!(m.start() == 0);
-                    (m.end(), i)
-                })
-                .next_back();
-            let (len, i) = if let Some((a, b)) = result {
+            let (len, i) = if let Some((a, b)) = self.matched() {
                 (a, b)
             } else {
+                // a whole character, which can take several bytes
+                let c = string.chars().next()?;
                 let loc = self
                     .source
-                    .range_to_location(self.position..self.position + 1);
-                report_error(&loc, &format!("unknown start of token: {}", &string[0..1]));
-                self.position += 1;
+                    .range_to_location(self.position..self.position + c.len_utf8());
+                report_error(
+                    ErrorCode::UnknownToken,
+                    &loc,
+                    &format!("unknown start of token: {c}"),
+                );
+                self.position += c.len_utf8();
                 return Some(Token {
                     kind: self.lexer.error(),
                     loc,
-                    text: string[0..1].to_string(),
+                    text: c.to_string(),
+                    leading: std::mem::take(&mut self.leading),
+                    trailing: vec![],
                 });
             };
 
+            let Some(kind) = self.lexer.kind(i) else {
+                if self.trivia {
+                    let t = self.read_trivia(len);
+                    self.leading.push(t);
+                } else {
+                    self.position += len;
+                }
+                continue;
+            };
             let loc = self
                 .source
                 .range_to_location(self.position..self.position + len);
-            let text = self.source.span(self.position..self.position + len);
+            let text = self
+                .source
+                .span(self.position..self.position + len)
+                .to_string();
             self.position += len;
-            if let Some(kind) = self.lexer.kind(i) {
-                return Some(Token {
-                    kind,
-                    loc,
-                    text: text.to_string(),
-                });
-            }
+            let trailing = if self.trivia {
+                self.trailing(loc.line)
+            } else {
+                vec![]
+            };
+            return Some(Token {
+                kind,
+                loc,
+                text,
+                leading: std::mem::take(&mut self.leading),
+                trailing,
+            });
         }
     }
 }
@@ -0,0 +1,37 @@
+//! Compiler of eclc scripts to the ECL files of the Touhou games.
+//!
+//! ```no_run
+//! let compiler = eclc::Compiler::new(Default::default());
+//! let mut session = eclc::Session::new(&compiler);
+//! match session.compile_str("stage.ecs", "sub main() { wait(60); }") {
+//!     Ok(bytes) => std::fs::write("stage.ecl", bytes)?,
+//!     Err(diagnostics) => {
+//!         for d in &diagnostics {
+//!             print!("{}", session.render(d));
+//!         }
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+#[macro_use]
+extern crate lazy_static;
+
+pub mod ast;
+mod code_gen;
+mod compiler;
+pub mod decompiler;
+pub mod ecl_instructions;
+pub mod error;
+mod error_code;
+mod grammar;
+mod grammar_file;
+mod lexer;
+pub mod lint;
+mod parser;
+pub mod scpt;
+mod suggest;
+
+pub use compiler::{Compiler, Session};
+pub use error::{Diagnostic, Error, ErrorCode, Severity};
+pub use lexer::{FileId, Location, SourceFile, SourceMap, Token, Tokens, Trivia};
+pub use lint::{Level, Lint, Lints};
@@ -0,0 +1,121 @@
+//! The warnings of the compiler. Each kind has a name, used on the command
+//! line to allow it, keep it a warning or make it an error.
+
+use std::collections::HashMap;
+
+use crate::error::{Diagnostic, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// A variable declared but never read.
+    UnusedVariables,
+    /// A sub that no instruction of the script calls.
+    UnusedSubs,
+    /// Instructions after a `return` or a `delete`.
+    UnreachableCode,
+    /// A variable with the name of a variable of an enclosing block.
+    ShadowedVariables,
+    /// An `if` or a `while` whose condition is always true or always false.
+    ConstantConditions,
+    /// A time label before the time already reached.
+    BackwardsTimeLabels,
+    /// A comment inside a statement, which the formatter moves after it.
+    MovedComments,
+    /// An int literal with a leading zero, which is octal.
+    OctalLiterals,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 8] = [
+        Lint::UnusedVariables,
+        Lint::UnusedSubs,
+        Lint::UnreachableCode,
+        Lint::ShadowedVariables,
+        Lint::ConstantConditions,
+        Lint::BackwardsTimeLabels,
+        Lint::MovedComments,
+        Lint::OctalLiterals,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedSubs => "unused_subs",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::ShadowedVariables => "shadowed_variables",
+            Lint::ConstantConditions => "constant_conditions",
+            Lint::BackwardsTimeLabels => "backwards_time_labels",
+            Lint::MovedComments => "moved_comments",
+            Lint::OctalLiterals => "octal_literals",
+        }
+    }
+
+    /// The lint called `name`, `-` being accepted for `_`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('-', "_");
+        Self::ALL.into_iter().find(|l| l.name() == name)
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// Not reported.
+    Allow,
+    /// Reported, the compilation goes on.
+    Warn,
+    /// Reported as an error.
+    Deny,
+}
+
+/// Level of each lint, every lint being a warning unless told otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct Lints {
+    levels: HashMap<Lint, Level>,
+}
+
+impl Lints {
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Sets the level of every lint.
+    pub fn set_all(&mut self, level: Level) {
+        for l in Lint::ALL {
+            self.levels.insert(l, level);
+        }
+    }
+
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels.get(&lint).copied().unwrap_or(Level::Warn)
+    }
+
+    /// `d` as its lint level makes it: `None` if it is allowed, an error
+    /// if it is denied. The diagnostics without lint are kept as they are.
+    pub fn apply(&self, mut d: Diagnostic) -> Option<Diagnostic> {
+        let Some(lint) = d.lint else {
+            return Some(d);
+        };
+        match self.level(lint) {
+            Level::Allow => return None,
+            Level::Warn => d.severity = Severity::Warning,
+            Level::Deny => d.severity = Severity::Error,
+        }
+        Some(d)
+    }
+
+    /// Why `lint` is reported, shown with its first diagnostic.
+    pub fn explain(&self, lint: Lint) -> Diagnostic {
+        let msg = match (self.level(lint), self.levels.contains_key(&lint)) {
+            (Level::Deny, _) => format!("`{lint}` is denied, `-W {lint}` makes it a warning"),
+            (_, false) => format!("`{lint}` is on by default, `-A {lint}` allows it"),
+            _ => format!("`{lint}` is set by `-W {lint}`"),
+        };
+        Diagnostic::simple(Severity::Note, &msg)
+    }
+}
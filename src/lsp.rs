@@ -0,0 +1,436 @@
+//! `eclc lsp`: a language server speaking the Language Server Protocol on
+//! stdin and stdout. A document is compiled again on every change for its
+//! diagnostics, and parsed as written for the names under the cursor.
+
+mod index;
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+};
+
+use serde_json::{json, Value};
+
+use eclc::{ast, Compiler, Diagnostic, Location, Session, Severity, SourceFile};
+
+use index::Index;
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// kinds of the completion items and of the symbols
+const COMPLETION_FUNCTION: u32 = 3;
+const COMPLETION_CONSTANT: u32 = 21;
+const SYMBOL_FUNCTION: u32 = 12;
+
+/// A document opened in the editor, as last sent.
+struct Document {
+    text: String,
+    index: Index,
+}
+
+struct Server<'c> {
+    compiler: &'c Compiler,
+    documents: HashMap<String, Document>,
+    /// Completed in every document: the instructions and the builtin constants.
+    completions: Vec<Value>,
+    /// `shutdown` was requested, the server exits cleanly.
+    shutdown: bool,
+}
+
+/// Serves the editor until it sends `exit`. Returns whether it asked for
+/// a shutdown before.
+pub fn run(compiler: &Compiler) -> io::Result<bool> {
+    let mut input = io::stdin().lock();
+    let mut out = io::stdout().lock();
+    let mut server = Server::new(compiler);
+    while let Some(body) = read_message(&mut input)? {
+        let msg = match serde_json::from_slice::<Value>(&body) {
+            Ok(msg) => msg,
+            Err(e) => {
+                let err = json!({ "code": PARSE_ERROR, "message": e.to_string() });
+                write_message(
+                    &mut out,
+                    &json!({ "jsonrpc": "2.0", "id": null, "error": err }),
+                )?;
+                continue;
+            }
+        };
+        if msg["method"] == "exit" {
+            return Ok(server.shutdown);
+        }
+        for reply in server.handle(&msg) {
+            write_message(&mut out, &reply)?;
+        }
+    }
+    Ok(false)
+}
+
+/// Body of the next message, `None` at the end of the input.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut len = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                len = value.trim().parse().ok();
+            }
+        }
+    }
+    let len = len.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "message without Content-Length")
+    })?;
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn write_message(out: &mut impl Write, msg: &Value) -> io::Result<()> {
+    let body = msg.to_string();
+    write!(out, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    out.flush()
+}
+
+impl<'c> Server<'c> {
+    fn new(compiler: &'c Compiler) -> Self {
+        let mut completions = Vec::new();
+        let mut names: Vec<_> = compiler.instructions.instruction_names().collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            let signatures: Vec<_> = compiler
+                .instructions
+                .ins_defs_for_name(name)
+                .iter()
+                .map(|d| d.signature())
+                .collect();
+            completions.push(json!({
+                "label": name,
+                "kind": COMPLETION_FUNCTION,
+                "detail": signatures.join("\n"),
+            }));
+        }
+        let prelude = SourceFile::from_string("<prelude>", ast::PRELUDE.to_owned());
+        let prelude = Session::new(compiler).parse(prelude).map(|p| p.consts);
+        let mut consts: Vec<_> = ast::builtins().into_keys().collect();
+        consts.extend(prelude.into_iter().flatten().map(|c| c.name.into_val()));
+        consts.sort();
+        for name in consts {
+            completions.push(json!({ "label": name, "kind": COMPLETION_CONSTANT }));
+        }
+        Self {
+            compiler,
+            documents: HashMap::new(),
+            completions,
+            shutdown: false,
+        }
+    }
+
+    /// The messages to send back for `msg`: the response to a request, the
+    /// diagnostics of a document that changed.
+    fn handle(&mut self, msg: &Value) -> Vec<Value> {
+        let Some(method) = msg["method"].as_str() else {
+            // a response, the server sends no request
+            return vec![];
+        };
+        let params = &msg["params"];
+        let Some(id) = msg.get("id") else {
+            return self.notification(method, params).into_iter().collect();
+        };
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // the whole document is sent on every change
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": {},
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "eclc", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/definition" => Ok(self.definition(params)),
+            "textDocument/completion" => Ok(self.completion(params)),
+            "textDocument/documentSymbol" => Ok(self.symbols(params)),
+            _ => Err(format!("unknown method `{method}`")),
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": e },
+            }),
+        }]
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Option<Value> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str()?;
+                Some(self.update(uri, text.to_owned()))
+            }
+            "textDocument/didChange" => {
+                let change = params["contentChanges"].as_array()?.last()?;
+                Some(self.update(uri, change["text"].as_str()?.to_owned()))
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                Some(publish(uri, vec![]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Compiles the new `text` of `uri` for its diagnostics, and indexes it.
+    fn update(&mut self, uri: &str, text: String) -> Value {
+        let path = uri_to_path(uri);
+        // a bug of the compiler mustn't take the editor's server down
+        let analysis = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut session = Session::new(self.compiler);
+            let _ = session.compile(SourceFile::from_string(&path, text.clone()));
+            let diagnostics: Vec<_> = session
+                .take_diagnostics()
+                .iter()
+                .filter_map(|d| lsp_diagnostic(&session, uri, &text, d))
+                .collect();
+            let src = SourceFile::from_string(&path, text.clone());
+            let docs = sub_docs(self.compiler, &src);
+            let mut session = Session::new(self.compiler);
+            let index = match session.parse(src) {
+                Ok(ecl) => Index::new(&ecl, &text, &docs, &self.compiler.instructions),
+                Err(_) => Index::default(),
+            };
+            (diagnostics, index)
+        }));
+        let (diagnostics, index) = analysis.unwrap_or_default();
+        self.documents
+            .insert(uri.to_owned(), Document { text, index });
+        publish(uri, diagnostics)
+    }
+
+    /// The document of `params` and the name at its position.
+    fn name_at(&self, params: &Value) -> Option<(&Document, &index::Name)> {
+        let doc = self
+            .documents
+            .get(params["textDocument"]["uri"].as_str()?)?;
+        let (line, col) = offset(&doc.text, &params["position"])?;
+        Some((doc, doc.index.at(line, col)?))
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some((doc, name)) = self.name_at(params) else {
+            return Value::Null;
+        };
+        let Some(hover) = &name.hover else {
+            return Value::Null;
+        };
+        let mut value = format!("```ecl\n{hover}\n```");
+        if let Some(doc) = &name.doc {
+            value.push_str("\n\n");
+            value.push_str(doc);
+        }
+        json!({
+            "contents": { "kind": "markdown", "value": value },
+            "range": range(&doc.text, &name.loc),
+        })
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let Some((doc, name)) = self.name_at(params) else {
+            return Value::Null;
+        };
+        match &name.def {
+            Some(def) => json!({
+                "uri": params["textDocument"]["uri"],
+                "range": range(&doc.text, def),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let mut items = self.completions.clone();
+        if let Some(doc) = params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri))
+        {
+            items.extend(
+                doc.index
+                    .consts
+                    .iter()
+                    .map(|c| json!({ "label": c, "kind": COMPLETION_CONSTANT })),
+            );
+        }
+        Value::Array(items)
+    }
+
+    fn symbols(&self, params: &Value) -> Value {
+        let Some(doc) = params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri))
+        else {
+            return Value::Null;
+        };
+        let symbols = doc.index.subs.iter().map(|s| {
+            let start = range(&doc.text, s.name.loc())["start"].clone();
+            let last = doc.text.split('\n').nth(s.last_line).unwrap_or("");
+            json!({
+                "name": s.name.val(),
+                "detail": s.signature,
+                "kind": SYMBOL_FUNCTION,
+                "range": {
+                    "start": start,
+                    "end": { "line": s.last_line, "character": last.encode_utf16().count() },
+                },
+                "selectionRange": range(&doc.text, s.name.loc()),
+            })
+        });
+        Value::Array(symbols.collect())
+    }
+}
+
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// `d` for the editor, if it is about the document `uri`, compiled first in
+/// `session`. The notes without location are added to the message.
+fn lsp_diagnostic(session: &Session, uri: &str, text: &str, d: &Diagnostic) -> Option<Value> {
+    let document = session.sources().get(Default::default()).filename.as_str();
+    let in_document = |loc: &Location| session.sources().get(loc.file).filename == document;
+    let range = match &d.loc {
+        Some(loc) if in_document(loc) => range(text, loc),
+        Some(_) => return None,
+        None => json!({
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": 0, "character": 0 },
+        }),
+    };
+    let mut message = d.message.clone();
+    let mut related = Vec::new();
+    for n in &d.notes {
+        match &n.loc {
+            Some(loc) if in_document(loc) => related.push(json!({
+                "location": { "uri": uri, "range": self::range(text, loc) },
+                "message": n.message,
+            })),
+            _ => message.push_str(&format!("\nnote: {}", n.message)),
+        }
+    }
+    let severity = match d.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+    let code = d
+        .code
+        .map(|c| c.code())
+        .or(d.lint.map(|l| l.name().to_owned()));
+    Some(json!({
+        "range": range,
+        "severity": severity,
+        "code": code,
+        "source": "eclc",
+        "message": message,
+        "relatedInformation": related,
+    }))
+}
+
+/// Range of `loc` in `text`, the columns counted in UTF-16 code units.
+fn range(text: &str, loc: &Location) -> Value {
+    let line = text.split('\n').nth(loc.line).unwrap_or("");
+    let col = |b: usize| line.get(..b).map_or(0, |s| s.encode_utf16().count());
+    json!({
+        "start": { "line": loc.line, "character": col(loc.span.start) },
+        "end": { "line": loc.line, "character": col(loc.span.end) },
+    })
+}
+
+/// Line and byte in the line of the LSP `position` in `text`.
+fn offset(text: &str, position: &Value) -> Option<(usize, usize)> {
+    let line_no = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let line = text.split('\n').nth(line_no)?;
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= character {
+            return Some((line_no, i));
+        }
+        units += c.len_utf16();
+    }
+    Some((line_no, line.len()))
+}
+
+/// The `///` comments right above each sub of `src`, by name. A blank line
+/// or another comment in between ends them.
+fn sub_docs(compiler: &Compiler, src: &SourceFile) -> HashMap<String, String> {
+    let mut docs = HashMap::new();
+    let mut tokens = compiler.tokens(src).with_trivia();
+    while let Some(t) = tokens.next() {
+        if t.kind != "kw_sub" {
+            continue;
+        }
+        let mut lines = Vec::new();
+        for trivia in t.leading.iter().rev() {
+            if let Some(line) = trivia.text.strip_prefix("///") {
+                lines.push(line.trim().to_owned());
+            } else if trivia.is_comment() || trivia.text.contains('\n') {
+                break;
+            }
+        }
+        let name = tokens.next().filter(|n| n.kind == "id");
+        if let (Some(name), false) = (name, lines.is_empty()) {
+            lines.reverse();
+            docs.insert(name.text, lines.join("\n"));
+        }
+    }
+    docs
+}
+
+/// Path of a `file://` URI, for the includes relative to the document.
+/// Another URI is kept as the name of the document.
+fn uri_to_path(uri: &str) -> String {
+    let Some(path) = uri.strip_prefix("file://") else {
+        return uri.to_owned();
+    };
+    let mut bytes = Vec::new();
+    let mut rest = path.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(c) if b == b'%' => {
+                bytes.push(c);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&bytes).into_owned();
+    // `/C:/dir` on Windows
+    match path.as_bytes() {
+        [b'/', _, b':', ..] => path[1..].to_owned(),
+        _ => path,
+    }
+}
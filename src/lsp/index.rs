@@ -0,0 +1,310 @@
+//! The names of a script as it is written, and what they refer to.
+
+use std::collections::HashMap;
+
+use eclc::{
+    ast::{Ecl, Expr, ExprType, Instr, Located, Param, Sub},
+    ecl_instructions::InstructionSet,
+    Location,
+};
+
+/// A name written in the script.
+pub struct Name {
+    pub loc: Location,
+    /// Where what it names is declared, if in the script.
+    pub def: Option<Location>,
+    /// What it is, like the signatures of an instruction.
+    pub hover: Option<String>,
+    /// The `///` comments above a sub, for the sub and its calls.
+    pub doc: Option<String>,
+}
+
+/// A sub, for the outline of the script.
+pub struct SubSymbol {
+    pub name: Located<String>,
+    pub signature: String,
+    /// Last line of the sub with an instruction.
+    pub last_line: usize,
+}
+
+#[derive(Default)]
+pub struct Index {
+    pub names: Vec<Name>,
+    pub subs: Vec<SubSymbol>,
+    /// The constants declared by the script.
+    pub consts: Vec<String>,
+}
+
+impl Index {
+    /// `text` is the source `ecl` was parsed from, only the names written
+    /// there being kept. `docs` are the doc comments of the subs, and
+    /// `instructions` the ones the calls refer to.
+    pub fn new(
+        ecl: &Ecl,
+        text: &str,
+        docs: &HashMap<String, String>,
+        instructions: &InstructionSet,
+    ) -> Self {
+        let mut b = Builder {
+            lines: text.split('\n').collect(),
+            docs,
+            instructions,
+            subs: HashMap::new(),
+            consts: HashMap::new(),
+            labels: HashMap::new(),
+            scopes: vec![],
+            names: vec![],
+        };
+        for s in &ecl.subs {
+            b.subs.insert(s.name.val(), (s.name.loc(), signature(s)));
+        }
+        for c in &ecl.consts {
+            let hover = format!("const {} {}", type_name(c.typ), c.name.val());
+            b.declared(&c.name, hover.clone());
+            b.consts.insert(c.name.val(), (c.name.loc(), hover));
+        }
+        for c in &ecl.consts {
+            b.expr(&c.value);
+        }
+        let mut subs = Vec::new();
+        for s in &ecl.subs {
+            b.sub(s);
+            let last_line = s
+                .instructions
+                .iter()
+                .filter_map(last_line)
+                .max()
+                .unwrap_or(s.name.loc().line);
+            subs.push(SubSymbol {
+                name: s.name.clone(),
+                signature: signature(s),
+                last_line,
+            });
+        }
+        Self {
+            names: b.names,
+            subs,
+            consts: ecl.consts.iter().map(|c| c.name.val().clone()).collect(),
+        }
+    }
+
+    /// The name written at the byte `col` of `line`, the end of a name
+    /// being still on it.
+    pub fn at(&self, line: usize, col: usize) -> Option<&Name> {
+        self.names
+            .iter()
+            .find(|n| n.loc.line == line && n.loc.span.start <= col && col <= n.loc.span.end)
+    }
+}
+
+fn type_name(t: ExprType) -> &'static str {
+    match t {
+        ExprType::Int => "int",
+        ExprType::Float => "float",
+        ExprType::String => "string",
+        ExprType::Vararg => "...",
+    }
+}
+
+/// `sub name(int a, float b)`.
+fn signature(s: &Sub) -> String {
+    let params: Vec<_> = s
+        .params
+        .iter()
+        .map(|p| match p {
+            Param::Int(n) => format!("int {}", n.val()),
+            Param::Float(n) => format!("float {}", n.val()),
+        })
+        .collect();
+    format!("sub {}({})", s.name.val(), params.join(", "))
+}
+
+fn last_line(i: &Instr) -> Option<usize> {
+    let inner = i.blocks().into_iter().flatten().filter_map(last_line).max();
+    inner.max(i.loc().map(|l| l.line))
+}
+
+/// Walks the script, the variables being looked up in the blocks enclosing
+/// them.
+struct Builder<'a> {
+    lines: Vec<&'a str>,
+    docs: &'a HashMap<String, String>,
+    instructions: &'a InstructionSet,
+    /// Declaration and signature of each sub.
+    subs: HashMap<&'a String, (&'a Location, String)>,
+    consts: HashMap<&'a String, (&'a Location, String)>,
+    /// Labels of the sub being walked, which a `goto` can reach from anywhere in it.
+    labels: HashMap<&'a String, &'a Location>,
+    /// Variables of each block enclosing the instruction walked, with their type.
+    scopes: Vec<Vec<(&'a Located<String>, &'static str)>>,
+    names: Vec<Name>,
+}
+
+impl<'a> Builder<'a> {
+    /// Whether `name` is written at `loc`, the names made by the parser
+    /// for `goto` or `return` not being in the source.
+    fn written(&self, name: &str, loc: &Location) -> bool {
+        let text = self
+            .lines
+            .get(loc.line)
+            .and_then(|l| l.get(loc.span.clone()));
+        text.is_some_and(|t| t.trim_start_matches('@') == name)
+    }
+
+    fn declared(&mut self, name: &Located<String>, hover: String) {
+        self.names.push(Name {
+            loc: name.loc().clone(),
+            def: Some(name.loc().clone()),
+            hover: Some(hover),
+            doc: None,
+        });
+    }
+
+    fn sub(&mut self, s: &'a Sub) {
+        self.labels.clear();
+        self.collect_labels(&s.instructions);
+        self.declared(&s.name, signature(s));
+        if let Some(n) = self.names.last_mut() {
+            n.doc = self.docs.get(s.name.val()).cloned();
+        }
+        let mut params = Vec::new();
+        for p in &s.params {
+            let (name, typ) = match p {
+                Param::Int(n) => (n, "int"),
+                Param::Float(n) => (n, "float"),
+            };
+            self.declared(name, format!("{typ} {}", name.val()));
+            params.push((name, typ));
+        }
+        self.scopes = vec![params];
+        self.instrs(&s.instructions);
+    }
+
+    fn collect_labels(&mut self, instrs: &'a [Instr]) {
+        for i in instrs {
+            if let Instr::Label(l) = i {
+                self.labels.insert(l.val(), l.loc());
+            }
+            for b in i.blocks() {
+                self.collect_labels(b);
+            }
+        }
+    }
+
+    fn instrs(&mut self, instrs: &'a [Instr]) {
+        self.scopes.push(vec![]);
+        for i in instrs {
+            self.instr(i);
+        }
+        self.scopes.pop();
+    }
+
+    fn instr(&mut self, i: &'a Instr) {
+        match i {
+            Instr::Label(l) => self.names.push(Name {
+                loc: l.loc().clone(),
+                def: Some(l.loc().clone()),
+                hover: None,
+                doc: None,
+            }),
+            Instr::Call(name, args) => self.call(name, args),
+            Instr::PushExpr(e) => self.expr(e),
+            Instr::If(cond, yes, no) => {
+                self.expr(cond);
+                self.instrs(yes);
+                self.instrs(no);
+            }
+            Instr::While(cond, l) | Instr::DoWhile(cond, l) => {
+                self.expr(cond);
+                self.instrs(l);
+            }
+            Instr::Bloc(l) | Instr::Loop(l) => self.instrs(l),
+            Instr::Affect(v, e) => {
+                self.expr(e);
+                self.id(v);
+            }
+            Instr::VarInt(v, e) | Instr::VarFloat(v, e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+                let typ = if let Instr::VarInt(..) = i {
+                    "int"
+                } else {
+                    "float"
+                };
+                self.declared(v, format!("{typ} {}", v.val()));
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.push((v, typ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn call(&mut self, name: &'a Located<String>, args: &'a [Expr]) {
+        let sub = match (name.val().as_str(), args.first()) {
+            ("ins_11" | "ins_15" | "ins_16", Some(Expr::Str(s))) => Some(s),
+            _ => None,
+        };
+        // `@sub(...)` is an `ins_11` located at the name of the sub
+        if let Some(s) = sub {
+            let target = self.subs.get(s.val());
+            self.names.push(Name {
+                loc: s.loc().clone(),
+                def: target.map(|t| t.0.clone()),
+                hover: target.map(|t| t.1.clone()),
+                doc: self.docs.get(s.val()).cloned(),
+            });
+        }
+        if self.written(name.val(), name.loc()) && sub.is_none_or(|s| s.loc() != name.loc()) {
+            let defs = self.instructions.ins_defs_for_name(name.val());
+            let signatures: Vec<_> = defs.iter().map(|d| d.signature()).collect();
+            self.names.push(Name {
+                loc: name.loc().clone(),
+                def: None,
+                hover: (!signatures.is_empty()).then(|| signatures.join("\n")),
+                doc: None,
+            });
+        }
+        for a in args {
+            self.expr(a);
+        }
+    }
+
+    fn expr(&mut self, e: &Expr) {
+        let mut ids = Vec::new();
+        e.clone().visit_mut(&mut |x| {
+            if let Expr::Id(id) = x {
+                ids.push(id.clone());
+            }
+        });
+        for id in &ids {
+            self.id(id);
+        }
+    }
+
+    /// An identifier: a variable, else a label, else a constant.
+    fn id(&mut self, id: &Located<String>) {
+        let var = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|s| s.iter().rev())
+            .find(|(v, _)| v.val() == id.val());
+        let (def, hover) = if let Some((v, typ)) = var {
+            (v.loc(), Some(format!("{typ} {}", v.val())))
+        } else if let Some(l) = self.labels.get(id.val()) {
+            (*l, None)
+        } else if let Some((loc, hover)) = self.consts.get(id.val()) {
+            (*loc, Some(hover.clone()))
+        } else {
+            return;
+        };
+        self.names.push(Name {
+            loc: id.loc().clone(),
+            def: Some(def.clone()),
+            hover,
+            doc: None,
+        });
+    }
+}
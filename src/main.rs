@@ -1,135 +1,525 @@
-#[macro_use]
-extern crate lazy_static;
-
 use crossterm::style::Stylize;
-use std::{io::Write, sync::Mutex};
-
-mod ast;
-mod code_gen;
-mod ecl_instructions;
-mod error;
-mod grammar;
-mod grammar_file;
-mod lexer;
-mod parser;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+};
+
+mod cli;
+mod lsp;
 mod print_bytes;
 
-use ast::AstNode;
-use error::{report_error_simple, Error};
-use grammar::Grammar;
-use lexer::{Lexer, SourceFile};
-use parser::ast::AstResolver;
+use cli::{Action, Command, Emit, MessageFormat, Options, Verbosity};
+use eclc::{
+    decompiler,
+    ecl_instructions::{self, InstructionSet},
+    Compiler, Diagnostic, Error, ErrorCode, Level, Session, Severity, SourceFile, SourceMap,
+};
 
-// Define a sample struct
+/// What a job prints. It is kept until the jobs of the previous inputs have
+/// printed theirs, so that the messages of files compiled at the same time
+/// don't mix.
 #[derive(Default)]
-struct Globals {
-    code_file: Option<SourceFile>,
+struct Log {
+    /// The output, for stdout.
+    out: Vec<u8>,
+    /// The diagnostics and the status messages, for stderr, which keeps them
+    /// out of an output piped somewhere else.
+    messages: Vec<u8>,
+    /// The diagnostics are printed as JSON, and the status messages not at all.
+    json: bool,
+    /// Codes of the errors printed, which `--explain` explains.
+    codes: Vec<ErrorCode>,
 }
 
-// Create a global mutable variable using lazy_static and Mutex
-lazy_static! {
-    static ref GLOBAL: Mutex<Globals> = Mutex::new(Globals::default());
+impl Log {
+    fn new(opts: &Options) -> Self {
+        Self {
+            out: vec![],
+            messages: vec![],
+            json: opts.message_format == MessageFormat::Json,
+            codes: vec![],
+        }
+    }
+
+    fn status(&mut self, verb: &str, msg: &str) {
+        if self.json {
+            return;
+        }
+        let verb = format!("{verb:>12}")
+            .bold()
+            .with(crossterm::style::Color::Green);
+        self.message(format!("{verb} {msg}\n").as_bytes());
+    }
+
+    fn error(&mut self, text: &str) {
+        let d = Diagnostic::simple(Severity::Error, text);
+        if self.json {
+            let json = d.to_json(&SourceMap::default()).to_string();
+            self.message(format!("{json}\n").as_bytes());
+        } else {
+            self.message(d.render(&SourceMap::default()).as_bytes());
+        }
+    }
+
+    /// Adds the diagnostics of `session` reported so far, the warnings
+    /// being left out when quiet. Returns how many of them are errors.
+    fn diagnostics(&mut self, session: &mut Session, verbosity: Verbosity) -> usize {
+        let diagnostics = session.take_diagnostics();
+        for d in &diagnostics {
+            if d.severity == Severity::Warning && verbosity == Verbosity::Quiet {
+                continue;
+            }
+            if self.json {
+                self.message(format!("{}\n", session.render_json(d)).as_bytes());
+            } else {
+                self.message(session.render(d).as_bytes());
+            }
+            if let Some(c) = d.code.filter(|c| !self.codes.contains(c)) {
+                self.codes.push(c);
+            }
+        }
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count()
+    }
+
+    /// Points to `--explain` for the codes of the errors printed.
+    fn explain_hint(&mut self) {
+        if self.json || self.codes.is_empty() {
+            return;
+        }
+        self.codes.sort_by_key(|c| c.code());
+        let hint = match &self.codes[..] {
+            [c] => format!("For more information about this error, try `eclc --explain {c}`."),
+            codes => {
+                let list: Vec<_> = codes.iter().map(|c| c.code()).collect();
+                format!(
+                    "Some errors have detailed explanations: {}.\n\
+                     For more information about an error, try `eclc --explain {}`.",
+                    list.join(", "),
+                    codes[0]
+                )
+            }
+        };
+        self.message(format!("{}\n", hint.bold()).as_bytes());
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn message(&mut self, bytes: &[u8]) {
+        self.messages.extend_from_slice(bytes);
+    }
+
+    fn print(&self) {
+        let mut stderr = std::io::stderr().lock();
+        let _ = stderr
+            .write_all(&self.messages)
+            .and_then(|_| stderr.flush());
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(&self.out).and_then(|_| stdout.flush());
+    }
+}
+
+fn print_status(opts: &Options, verb: &str, msg: &str) {
+    let mut log = Log::new(opts);
+    log.status(verb, msg);
+    log.print();
+}
+
+/// Runs `job` on every input, `opts.jobs` of them at the same time, and
+/// prints their logs in the order of the inputs. Returns how many failed.
+fn run_jobs<F>(opts: &Options, job: F) -> usize
+where
+    F: Fn(&str, &mut Log) -> bool + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|s| {
+        for _ in 0..opts.jobs.min(opts.inputs.len()) {
+            let (next, job, tx) = (&next, &job, tx.clone());
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = opts.inputs.get(i) else {
+                    break;
+                };
+                let mut log = Log::new(opts);
+                let ok = job(input, &mut log);
+                let _ = tx.send((i, log, ok));
+            });
+        }
+        drop(tx);
+
+        let mut failed = 0;
+        let mut done = BTreeMap::new();
+        let mut printed = 0;
+        for (i, log, ok) in rx {
+            failed += usize::from(!ok);
+            done.insert(i, log);
+            while let Some(log) = done.remove(&printed) {
+                log.print();
+                printed += 1;
+            }
+        }
+        failed
+    })
 }
 
 fn gen_file(
     fname: &str,
-    lexer: &Lexer<&str>,
-    grammar: &Grammar,
-    ast_resolver: &AstResolver<AstNode>,
+    output: Option<&str>,
+    opts: &Options,
+    session: &mut Session,
+    log: &mut Log,
 ) -> Result<(), Error> {
-    // Open code file
-    GLOBAL.lock().unwrap().code_file = Some(lexer::SourceFile::open(fname).map_err(Error::IO)?);
+    let src = SourceFile::open(fname).map_err(Error::IO)?;
+    let node = session.compile(src)?;
 
-    let tokens = {
-        let lock = GLOBAL.lock().unwrap();
-        lexer.tokens(lock.code_file.as_ref().unwrap())
+    let out = match opts.emit {
+        Emit::Ast => format!("{:#?}\n", node).into_bytes(),
+        // generate binary
+        Emit::Bin => session.generate(&node),
+        Emit::Hex => print_bytes::dump(&session.generate(&node)).into_bytes(),
     };
 
-    // Parse code
-    let mut node = ast_resolver
-        .resolve(
-            &parser::parse(grammar, tokens, "Ecl")
-                .ok_or(Error::Simple("Could not parse node: Aborting".to_owned()))?,
-            &[],
-        )?
-        .ecl();
-    // println!("{:#?}", node);
-
-    // Process code for binary generation
-    node.process()?;
-    // println!("{:#?}", node);
-
-    // generate binary
-    let bytes = code_gen::generate(&node);
-    // print_bytes::pr(&bytes);
-    std::fs::File::create("out.ecl")
-        .map_err(Error::IO)?
-        .write_all(&bytes)
-        .map_err(Error::IO)?;
+    write_output(output, &out, opts, log)
+}
 
-    Ok(())
+/// Compiles `fname`, decompiles the result and compiles it again:
+/// both binaries have to be the same.
+fn verify_file(
+    fname: &str,
+    output: Option<&str>,
+    compiler: &Compiler,
+    opts: &Options,
+    session: &mut Session,
+    log: &mut Log,
+) -> Result<(), Error> {
+    let src = SourceFile::open(fname).map_err(Error::IO)?;
+    let first = session.compile(src)?;
+    let first = session.generate(&first);
+
+    let decompiled = decompiler::decompile(&first, &compiler.instructions, opts.encoding)
+        .inspect_err(|e| {
+            if let Error::Simple(s) = e {
+                log.error(&format!("{}: could not decompile: {}", fname, s));
+            }
+        })?;
+    if let Some(o) = output {
+        write_output(Some(o), decompiled.as_bytes(), opts, log)?;
+    }
+    let src = SourceFile::from_string(&format!("{fname} (decompiled)"), decompiled);
+    // the warnings are about the source, not about what the decompiler makes of it
+    session.lints.set_all(Level::Allow);
+    let second = session.compile(src)?;
+    let second = session.generate(&second);
+
+    let mismatch = first
+        .iter()
+        .zip(&second)
+        .position(|(a, b)| a != b)
+        .or((first.len() != second.len()).then(|| first.len().min(second.len())));
+    match mismatch {
+        None => Ok(()),
+        Some(offset) => {
+            log.error(&format!(
+                "{}: round trip mismatch at byte {:#x}, {} ({} bytes, {} after the round trip)",
+                fname,
+                offset,
+                decompiler::locate(&first, offset),
+                first.len(),
+                second.len()
+            ));
+            Err(Error::Simple("round trip mismatch".to_owned()))
+        }
+    }
 }
 
-fn main_sub() -> Result<(), Error> {
-    // Read grammar
-    let mut gf = grammar_file::GrammarFile::from_file("test.grammar").map_err(Error::IO)?;
-    let rulestrings = grammar_file::parse_rules(&mut gf);
-    let mut grammar = grammar::Grammar::from_rule_string(rulestrings);
-    grammar.calculate_first_sets();
-    grammar.calculate_follow_sets();
+/// Writes `out` to the file `output`, or to the log, which goes to stdout.
+fn write_output(
+    output: Option<&str>,
+    out: &[u8],
+    opts: &Options,
+    log: &mut Log,
+) -> Result<(), Error> {
+    match output {
+        Some(o) => {
+            if opts.verbosity == Verbosity::Verbose {
+                log.status("Writing", &format!("`{}` ({} bytes)", o, out.len()));
+            }
+            std::fs::File::create(o)
+                .map_err(Error::IO)?
+                .write_all(out)
+                .map_err(Error::IO)?;
+        }
+        None => log.write(out),
+    }
+    Ok(())
+}
 
-    if !crate::grammar::is_ll1_grammar(&grammar) {
-        return Err(Error::Grammar("The grammar is not LL1".to_owned()));
+fn decompile_file(
+    fname: &str,
+    output: Option<&str>,
+    instructions: &InstructionSet,
+    opts: &Options,
+    log: &mut Log,
+) -> Result<(), Error> {
+    let bytes = if fname == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        std::fs::read(fname)
     }
+    .map_err(Error::IO)?;
+    let src = decompiler::decompile(&bytes, instructions, opts.encoding)?;
+    write_output(output, src.as_bytes(), opts, log)
+}
 
-    let lexer = gf.lexer();
-    let mut ast_resolver = parser::ast::AstResolver::default();
-    ast_resolver.set_ast_prod(grammar.get_ast_prod());
-    ast::fill_executor(&mut ast_resolver);
+fn decompile(src_name: &str, instructions: &InstructionSet, opts: &Options, log: &mut Log) -> bool {
+    let output = opts.output_for(src_name);
+    let Err(e) = decompile_file(src_name, output.as_deref(), instructions, opts, log) else {
+        return true;
+    };
+    match e {
+        Error::IO(e) => log.error(&format!("{}: {}", src_name, e)),
+        Error::Simple(s) => log.error(&format!("{}: {}", src_name, s)),
+        _ => {}
+    }
+    log.error(&format!("could not decompile `{}`", src_name));
+    false
+}
 
-    let src_name = "test.code";
-    let bin_name = "out.ecl";
+/// ` due to 3 previous errors`, after the errors reported for a file.
+fn due_to(errors: usize) -> String {
+    match errors {
+        0 => String::new(),
+        1 => " due to 1 previous error".to_owned(),
+        n => format!(" due to {n} previous errors"),
+    }
+}
 
-    println!(
-        "   {} `{}` from source `{}`",
-        "Compiling".bold().with(crossterm::style::Color::Green),
-        bin_name,
-        src_name
+fn verify(src_name: &str, compiler: &Compiler, opts: &Options, log: &mut Log) -> bool {
+    let output = opts.output_for(src_name);
+    let mut session = Session::new(compiler);
+    let res = verify_file(
+        src_name,
+        output.as_deref(),
+        compiler,
+        opts,
+        &mut session,
+        log,
     );
-    if let Err(_) = gen_file(src_name, &lexer, &grammar, &ast_resolver) {
-        report_error_simple(&format!(
-            "could not compile `{}` (bin \"{}\") due to previous error",
-            src_name, bin_name
-        ));
-    } else {
-        println!(
-            "    {} building `{}`",
-            "Finished".bold().with(crossterm::style::Color::Green),
-            bin_name
-        );
+    let mut errors = log.diagnostics(&mut session, opts.verbosity);
+    match res {
+        Ok(()) => {
+            if opts.verbosity >= Verbosity::Normal {
+                log.status("Verified", &format!("`{}`", src_name));
+            }
+            true
+        }
+        Err(e) => {
+            if let (Error::IO(e), 0) = (e, errors) {
+                log.error(&format!("{}: {}", src_name, e));
+                errors = 1;
+            }
+            log.error(&format!(
+                "could not verify `{}`{}",
+                src_name,
+                due_to(errors)
+            ));
+            log.explain_hint();
+            false
+        }
     }
+}
 
-    Ok(())
+/// Source of `fname` and the same formatted.
+fn fmt_file(fname: &str, session: &mut Session) -> Result<(String, String), Error> {
+    let src = SourceFile::open(fname).map_err(Error::IO)?;
+    let text = src.content.clone();
+    let formatted = session.format(src)?;
+    Ok((text, formatted))
 }
 
-fn main() {
-    if let Err(e) = main_sub() {
-        match e {
-            Error::Simple(s) => {
-                println!("There was an error and the compilation couldn't finish:\n{s}");
+/// Number of the first line which differs between `a` and `b`.
+fn first_difference(a: &str, b: &str) -> Option<usize> {
+    let (a, b): (Vec<_>, Vec<_>) = (a.split('\n').collect(), b.split('\n').collect());
+    let line = a.iter().zip(&b).position(|(x, y)| x != y);
+    line.or((a.len() != b.len()).then(|| a.len().min(b.len())))
+        .map(|l| l + 1)
+}
+
+fn fmt(src_name: &str, compiler: &Compiler, opts: &Options, log: &mut Log) -> bool {
+    let output = opts.output_for(src_name);
+    let mut session = Session::new(compiler);
+    let res = fmt_file(src_name, &mut session);
+    let mut errors = log.diagnostics(&mut session, opts.verbosity);
+    let res = res.and_then(|(text, formatted)| {
+        let changed = first_difference(&text, &formatted);
+        if opts.check {
+            if let Some(line) = changed {
+                log.error(&format!(
+                    "`{src_name}` is not formatted (first difference on line {line})"
+                ));
+                return Ok(false);
             }
-            Error::Grammar(s) => {
-                println!("Grammar error: {s}");
+        } else if changed.is_some() || output.as_deref() != Some(src_name) {
+            write_output(output.as_deref(), formatted.as_bytes(), opts, log)?;
+            if opts.verbosity >= Verbosity::Normal && output.is_some() {
+                log.status("Formatted", &format!("`{src_name}`"));
             }
-            Error::BackEnd(s) => {
-                println!("BackEnd error: {s}");
+        }
+        Ok(true)
+    });
+    match res {
+        Ok(done) => done,
+        Err(e) => {
+            if let (Error::IO(e), 0) = (e, errors) {
+                log.error(&format!("{}: {}", src_name, e));
+                errors = 1;
             }
-            Error::ShouldNeverBeThere => {
-                println!("An error occured that should have never occured");
+            log.error(&format!(
+                "could not format `{}`{}",
+                src_name,
+                due_to(errors)
+            ));
+            log.explain_hint();
+            false
+        }
+    }
+}
+
+fn build(src_name: &str, compiler: &Compiler, opts: &Options, log: &mut Log) -> bool {
+    let output = opts.output_for(src_name);
+    let mut session = Session::new(compiler);
+    let bin_name = output.as_deref().unwrap_or("<stdout>");
+    if opts.verbosity >= Verbosity::Normal && output.is_some() {
+        log.status(
+            "Compiling",
+            &format!("`{}` from source `{}`", bin_name, src_name),
+        );
+    }
+    let res = gen_file(src_name, output.as_deref(), opts, &mut session, log);
+    let mut errors = log.diagnostics(&mut session, opts.verbosity);
+    if let Err(e) = res {
+        if let (Error::IO(e), 0) = (e, errors) {
+            log.error(&format!("{}: {}", src_name, e));
+            errors = 1;
+        }
+        log.error(&format!(
+            "could not compile `{}` (bin \"{}\"){}",
+            src_name,
+            bin_name,
+            due_to(errors)
+        ));
+        log.explain_hint();
+        return false;
+    }
+    if opts.verbosity >= Verbosity::Normal && output.is_some() {
+        log.status("Finished", &format!("building `{}`", bin_name));
+    }
+    true
+}
+
+fn main_sub(opts: &Options) -> Result<usize, Error> {
+    let mut instructions = InstructionSet::default();
+    if let Some(map) = &opts.map {
+        if opts.verbosity == Verbosity::Verbose {
+            print_status(opts, "Loading", &format!("instruction map `{}`", map));
+        }
+        instructions.load_map(&ecl_instructions::EclMap::open(map)?)?;
+    }
+
+    if opts.command == Command::Decompile {
+        return Ok(run_jobs(opts, |src_name, log| {
+            decompile(src_name, &instructions, opts, log)
+        }));
+    }
+
+    let mut compiler = match &opts.grammar {
+        Some(grammar) => {
+            if opts.verbosity == Verbosity::Verbose {
+                print_status(opts, "Loading", &format!("grammar `{}`", grammar));
             }
-            Error::IO(e) => {
-                println!("IO error: {}", e);
+            Compiler::from_grammar(grammar, opts.encoding)?
+        }
+        None => Compiler::new(opts.encoding),
+    };
+    compiler.instructions = instructions;
+    compiler.lints = opts.lints.clone();
+
+    if opts.command == Command::Lsp {
+        let clean = lsp::run(&compiler).map_err(Error::IO)?;
+        return Ok(usize::from(!clean));
+    }
+
+    Ok(run_jobs(opts, |src_name, log| match opts.command {
+        Command::Verify => verify(src_name, &compiler, opts, log),
+        Command::Fmt => fmt(src_name, &compiler, opts, log),
+        _ => build(src_name, &compiler, opts, log),
+    }))
+}
+
+fn main() -> ExitCode {
+    let opts = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Action::Run(opts)) => opts,
+        Ok(Action::Help) => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Action::Version) => {
+            println!("eclc {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Ok(Action::Explain(code)) => {
+            let Some(code) = ErrorCode::from_code(&code) else {
+                let mut log = Log::default();
+                log.error(&format!("`{code}` is not an error code of eclc"));
+                log.print();
+                return ExitCode::from(2);
+            };
+            print!("{}", code.explanation());
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            if let Error::Simple(s) = e {
+                let mut log = Log::default();
+                log.error(&s);
+                log.print();
+            }
+            return ExitCode::from(2);
+        }
+    };
+
+    match main_sub(&opts) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            match e {
+                Error::Simple(s) | Error::Type(s, _) => {
+                    eprintln!("There was an error and the compilation couldn't finish:\n{s}");
+                }
+                Error::Grammar(s) => {
+                    eprintln!("Grammar error: {s}");
+                }
+                Error::BackEnd(s) => {
+                    eprintln!("BackEnd error: {s}");
+                }
+                Error::ShouldNeverBeThere => {
+                    eprintln!("An error occured that should have never occured");
+                }
+                Error::IO(e) => {
+                    eprintln!("IO error: {}", e);
+                }
             }
+            ExitCode::FAILURE
         }
     }
 }
@@ -1,10 +1,12 @@
 use crate::ast::tok_name_for_error;
 use crate::error::report_error;
 use crate::error::report_error_ext;
+use crate::error::ErrorCode;
 use crate::grammar::get_production_table_entry;
 use crate::grammar::get_production_table_tokens_for_nt;
-use crate::grammar::Grammar;
+use crate::grammar::ProductionTable;
 use crate::grammar::Symbol;
+use crate::lexer::Token;
 use crate::lexer::Tokens;
 
 pub mod ast;
@@ -15,26 +17,133 @@ struct NodeAndTimes<'a> {
     pub times: usize,
 }
 
-pub fn parse<'a>(grammar: &Grammar, tokens: Tokens<'a, &str>, first_nt: &str) -> Option<Node<'a>> {
+/// A non terminal the parser recovers in after a syntax error: its node
+/// becomes an error node, and the tokens are skipped up to its end.
+struct SyncPoint {
+    nt: &'static str,
+    /// The list of `nt`s, `list ::= nt list`, where an error node is added
+    /// when a token can't start an `nt`.
+    list: &'static str,
+    /// Tokens ending an `nt`, skipped with it.
+    ends: &'static [&'static str],
+    /// Tokens of what comes after an `nt`.
+    stops: &'static [&'static str],
+}
+
+/// Statements end at `;` or at the end of their block, top-level items
+/// at the start of the next one.
+const SYNC_POINTS: &[SyncPoint] = &[
+    SyncPoint {
+        nt: "Instr",
+        list: "InstrList",
+        ends: &[";"],
+        stops: &["rb", "kw_sub"],
+    },
+    SyncPoint {
+        nt: "Item",
+        list: "ItemList",
+        ends: &[],
+        stops: &["kw_sub", "kw_const", "kw_enum", "kw_include"],
+    },
+];
+
+/// Skips the tokens up to the end of the `sp` being parsed, or up to what
+/// follows it. The braces opened on the way are skipped with their content.
+fn skip_to_sync<'a>(
+    sp: &SyncPoint,
+    tokens: &mut Tokens<'a, String>,
+    cur_token_opt: &mut Option<Token<&'a str>>,
+) {
+    let mut depth = 0usize;
+    while let Some(cur_token) = cur_token_opt {
+        let kind = cur_token.kind;
+        if kind == "EOF" || (depth == 0 || kind != "rb") && sp.stops.contains(&kind) {
+            return;
+        }
+        *cur_token_opt = tokens.next();
+        match kind {
+            "lb" => depth += 1,
+            "rb" => depth = depth.saturating_sub(1),
+            _ if depth == 0 && sp.ends.contains(&kind) => return,
+            _ => {}
+        }
+    }
+}
+
+/// Goes on after a syntax error on the current token, at the innermost
+/// sync point: either the failing symbol is a list of them, and an error
+/// node is added to it, or one is being parsed, and it is replaced by an
+/// error node. Returns false if there is no such point.
+fn recover<'a>(
+    tokens: &mut Tokens<'a, String>,
+    cur_token_opt: &mut Option<Token<&'a str>>,
+    node_stack: &mut Vec<NodeAndTimes<'a>>,
+    symbols_to_derive: &mut Vec<Symbol>,
+) -> bool {
+    let kind = cur_token_opt.as_ref().map_or("EOF", |t| t.kind);
+    if let Symbol::NT(nt) = &symbols_to_derive[0] {
+        // a token has to be skipped, or the list would fail on it again
+        let list = SYNC_POINTS
+            .iter()
+            .find(|sp| sp.list == nt && kind != "EOF" && !sp.stops.contains(&kind));
+        if let Some(sp) = list {
+            skip_to_sync(sp, tokens, cur_token_opt);
+            node_stack.push(NodeAndTimes {
+                node: Node::NT(sp.list.to_owned(), vec![Node::Error(sp.nt.to_owned())]),
+                times: 1,
+            });
+            return true;
+        }
+    }
+
+    let Some((k, sp)) = node_stack.iter().enumerate().rev().find_map(|(k, n)| {
+        let sp = SYNC_POINTS.iter().find(|sp| sp.nt == n.node.name())?;
+        Some((k, sp))
+    }) else {
+        return false;
+    };
+    let (parents, replaced) = node_stack.split_at_mut(k);
+    // the error node goes in the parent of the replaced one
+    let Some(NodeAndTimes { node: Node::NT(_, v), times }) = parents.last_mut() else {
+        return false;
+    };
+    v.push(Node::Error(sp.nt.to_owned()));
+    *times -= 1;
+    // the symbols left of the replaced nodes, without the ones being parsed
+    let left: usize = replaced.iter().map(|n| n.times).sum();
+    symbols_to_derive.drain(..left - (replaced.len() - 1));
+    node_stack.truncate(k);
+    skip_to_sync(sp, tokens, cur_token_opt);
+    true
+}
+
+pub fn parse<'a>(
+    parsing_table: &ProductionTable,
+    tokens: Tokens<'a, String>,
+    first_nt: &str,
+) -> Option<Node<'a>> {
     let mut tokens = tokens;
-    let parsing_table = grammar.fill_ll1_production_table();
     let mut symbols_to_derive = vec![Symbol::NT(first_nt.to_string())];
     let mut cur_token_opt = tokens.next();
 
     let mut node_stack: Vec<NodeAndTimes> = vec![];
     let mut ret = None;
+    // after an error, the next ones are only reported once a token matched
+    let mut recovering = false;
 
     while !symbols_to_derive.is_empty() {
         let Some(ref cur_token) = cur_token_opt else { break; };
         let s = &symbols_to_derive[0];
         if cur_token.kind == "ERROR" {
-            // report_error(&cur_token.loc, "Couldn't continue parsing at unknown token");
-            return None;
+            // already reported by the lexer
+            cur_token_opt = tokens.next();
+            continue;
         }
+        let mut failed = false;
         match s {
             Symbol::NT(nt) => {
                 if let Some(parsing_table_entry) =
-                    get_production_table_entry(&parsing_table, nt, cur_token.kind)
+                    get_production_table_entry(parsing_table, nt, cur_token.kind)
                 {
                     let mut rule_symbols = parsing_table_entry;
                     node_stack.push(NodeAndTimes {
@@ -44,9 +153,10 @@ pub fn parse<'a>(grammar: &Grammar, tokens: Tokens<'a, &str>, first_nt: &str) ->
                     rule_symbols.extend(symbols_to_derive.into_iter().skip(1));
                     symbols_to_derive = rule_symbols;
                 } else {
-                    let expected_tokens = get_production_table_tokens_for_nt(&parsing_table, nt);
+                    let expected_tokens = get_production_table_tokens_for_nt(parsing_table, nt);
                     if expected_tokens.is_empty() {
                         report_error(
+                            ErrorCode::GrammarFile,
                             &cur_token.loc,
                             &format!("No production table entry for non terminal \"{}\"", nt),
                         );
@@ -66,8 +176,15 @@ pub fn parse<'a>(grammar: &Grammar, tokens: Tokens<'a, &str>, first_nt: &str) ->
                     let under_error = error_message.clone();
                     error_message
                         .push_str(&format!(", found {}", tok_name_for_error(cur_token.kind)));
-                    report_error_ext(&cur_token.loc, &error_message, &under_error);
-                    return None;
+                    if !recovering {
+                        report_error_ext(
+                            ErrorCode::Syntax,
+                            &cur_token.loc,
+                            &error_message,
+                            &under_error,
+                        );
+                    }
+                    failed = true;
                 }
             }
             Symbol::T(t) => {
@@ -78,20 +195,35 @@ pub fn parse<'a>(grammar: &Grammar, tokens: Tokens<'a, &str>, first_nt: &str) ->
                     });
                     cur_token_opt = tokens.next();
                     symbols_to_derive = symbols_to_derive[1..].to_vec();
+                    recovering = false;
                 } else {
-                    report_error_ext(
-                        &cur_token.loc,
-                        &format!(
-                            "Expected {}, found {}",
-                            tok_name_for_error(t),
-                            tok_name_for_error(cur_token.kind)
-                        ),
-                        &format!("Expected {}", tok_name_for_error(t)),
-                    );
-                    return None;
+                    if !recovering {
+                        report_error_ext(
+                            ErrorCode::Syntax,
+                            &cur_token.loc,
+                            &format!(
+                                "Expected {}, found {}",
+                                tok_name_for_error(t),
+                                tok_name_for_error(cur_token.kind)
+                            ),
+                            &format!("Expected {}", tok_name_for_error(t)),
+                        );
+                    }
+                    failed = true;
                 }
             }
         }
+        if failed {
+            if !recover(
+                &mut tokens,
+                &mut cur_token_opt,
+                &mut node_stack,
+                &mut symbols_to_derive,
+            ) {
+                return None;
+            }
+            recovering = true;
+        }
         while let Some(n) = node_stack.iter().last() {
             if n.times == 0 {
                 let n = node_stack.pop().unwrap();
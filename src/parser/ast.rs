@@ -1,33 +1,26 @@
-mod micro_lang;
-
 use crate::error::Error;
+use crate::grammar::micro_lang::{AstDef, Der};
 use crate::lexer::Token;
-pub use micro_lang::{parse_ast_def, AstDef};
 
 #[derive(Debug)]
 pub enum Node<'a> {
     NT(String, Vec<Node<'a>>),
     T(Token<&'a str>),
+    /// The non terminal that couldn't be parsed, after a syntax error.
+    Error(String),
 }
 
 impl<'a> Node<'a> {
     pub fn name(&self) -> &str {
         match self {
-            Self::T(Token {
-                kind,
-                loc: _,
-                text: _,
-            }) => kind,
-            Self::NT(s, _) => s,
+            Self::T(Token { kind, .. }) => kind,
+            Self::NT(s, _) | Self::Error(s) => s,
         }
     }
     pub fn string_rep(&self) -> String {
         match self {
-            Self::T(Token {
-                kind,
-                loc: _,
-                text: _,
-            }) => kind.to_string(),
+            Self::T(Token { kind, .. }) => kind.to_string(),
+            Self::Error(s) => format!("{s} ::= error "),
             Self::NT(s, c) => {
                 let mut s = s.clone();
                 s.push_str(" ::= ");
@@ -44,6 +37,7 @@ impl<'a> Node<'a> {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Default)]
 pub struct AstNode {
     pub name: String, // or content
@@ -52,6 +46,8 @@ pub struct AstNode {
 
 pub trait NeededForAstNode: Clone {
     fn from_token(tok: &Token<&str>) -> Result<Self, Error>;
+    /// The node of a non terminal `nt` that couldn't be parsed.
+    fn from_error(nt: &str) -> Self;
 }
 
 impl NeededForAstNode for AstNode {
@@ -61,11 +57,18 @@ impl NeededForAstNode for AstNode {
             children: vec![],
         })
     }
+
+    fn from_error(nt: &str) -> Self {
+        Self {
+            name: format!("error({nt})"),
+            children: vec![],
+        }
+    }
 }
 
 use std::collections::HashMap;
 
-type ExecutorFunction<N> = Box<dyn Fn(&[String], &[N]) -> Result<N, Error>>;
+type ExecutorFunction<N> = Box<dyn Fn(&[String], &[N]) -> Result<N, Error> + Send + Sync>;
 
 #[derive(Default)]
 pub struct FnExecutor<N: NeededForAstNode> {
@@ -100,15 +103,57 @@ impl<N: NeededForAstNode> AstResolver<N> {
                 prod.execute(self, c, params)
             }
             Node::T(tok) => N::from_token(tok),
+            Node::Error(nt) => Ok(N::from_error(nt)),
         }
     }
 
     pub fn add_func<T>(&mut self, name: &str, f: T)
     where
-        T: Fn(&[String], &[N]) -> Result<N, Error> + 'static,
+        T: Fn(&[String], &[N]) -> Result<N, Error> + Send + Sync + 'static,
     {
         self.executor
             .functions
             .insert(name.to_string(), Box::new(f));
     }
 }
+
+impl Der {
+    pub fn execute<N: NeededForAstNode>(
+        &self,
+        resolver: &AstResolver<N>,
+        children: &[Node],
+        params: &[N],
+    ) -> Result<N, Error> {
+        match self {
+            Self::Child(n) => resolver.resolve(&children[*n], &[]),
+            Self::ChildDer(n, par) => {
+                let tmp: Result<Vec<N>, Error> = par
+                    .iter()
+                    .map(|p| p.execute(resolver, children, params))
+                    .collect();
+                resolver.resolve(&children[*n], &tmp?)
+            }
+            Self::Param(n) => Ok(params[*n].clone()),
+        }
+    }
+}
+
+impl AstDef {
+    pub fn execute<N: NeededForAstNode>(
+        &self,
+        resolver: &AstResolver<N>,
+        children: &[Node],
+        params: &[N],
+    ) -> Result<N, Error> {
+        match self {
+            Self::Der(d) => d.execute(resolver, children, params),
+            Self::Fun(fun_name, par) => {
+                let par: Result<Vec<N>, Error> = par
+                    .iter()
+                    .map(|p| p.execute(resolver, children, params))
+                    .collect();
+                resolver.executor.exec(fun_name, &par?)
+            }
+        }
+    }
+}
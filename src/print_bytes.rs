@@ -1,15 +1,18 @@
 #![allow(dead_code)]
-pub fn pr(bytes: &[u8]) {
+use std::fmt::Write;
+
+pub fn dump(bytes: &[u8]) -> String {
+    let mut s = String::new();
     for i in 0..=bytes.len() / 16 {
         for j in 0..16 {
             let pos = j + i * 16;
             if pos < bytes.len() {
-                print!("{:02X}", bytes[pos]);
+                write!(s, "{:02X}", bytes[pos]).unwrap();
             } else {
-                print!("  ");
+                s.push_str("  ");
             }
             if j % 4 == 3 {
-                print!(" ");
+                s.push(' ');
             }
         }
         for j in 0..16 {
@@ -17,13 +20,17 @@ pub fn pr(bytes: &[u8]) {
             if pos < bytes.len() {
                 let b = bytes[pos];
                 if b.is_ascii_punctuation() || b.is_ascii_alphanumeric() {
-                    let c = char::from(b);
-                    print!("{}", c);
+                    s.push(char::from(b));
                 } else {
-                    print!(".");
+                    s.push('.');
                 }
             }
         }
-        println!();
+        s.push('\n');
     }
+    s
+}
+
+pub fn pr(bytes: &[u8]) {
+    print!("{}", dump(bytes));
 }
@@ -0,0 +1,59 @@
+use crate::error::Error;
+
+/// Encoding of the strings stored in the files: string arguments and include names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    Utf8,
+    /// What the games read.
+    #[default]
+    ShiftJis,
+}
+
+impl Encoding {
+    pub fn from_arg(s: &str) -> Result<Self, Error> {
+        match s {
+            "utf8" | "utf-8" => Ok(Self::Utf8),
+            "sjis" | "shift-jis" | "shift_jis" => Ok(Self::ShiftJis),
+            _ => Err(Error::Simple(format!(
+                "unknown encoding `{s}` (expected utf8 or sjis)"
+            ))),
+        }
+    }
+
+    /// Encodes `s`, or returns the byte range in `s` of the first character
+    /// the encoding can't represent.
+    pub fn encode(self, s: &str) -> Result<Vec<u8>, std::ops::Range<usize>> {
+        match self {
+            Self::Utf8 => Ok(s.as_bytes().to_vec()),
+            Self::ShiftJis => {
+                let mut out = Vec::with_capacity(s.len());
+                for (i, c) in s.char_indices() {
+                    let mut buf = [0; 4];
+                    let (b, _, unmappable) = encoding_rs::SHIFT_JIS.encode(c.encode_utf8(&mut buf));
+                    if unmappable {
+                        return Err(i..i + c.len_utf8());
+                    }
+                    out.extend_from_slice(&b);
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decodes `b`, replacing the invalid sequences.
+    pub fn decode(self, b: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(b).into_owned(),
+            Self::ShiftJis => encoding_rs::SHIFT_JIS.decode(b).0.into_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "UTF-8"),
+            Self::ShiftJis => write!(f, "Shift-JIS"),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::Error;
+
+/// Little-endian output buffer, the counterpart of `Reader`.
+#[derive(Debug, Default)]
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pos(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn bytes(&mut self, b: &[u8]) {
+        self.bytes.extend_from_slice(b);
+    }
+
+    pub fn zeros(&mut self, n: usize) {
+        self.bytes.resize(self.bytes.len() + n, 0);
+    }
+
+    pub fn u8(&mut self, v: u8) {
+        self.bytes.push(v);
+    }
+
+    pub fn u16(&mut self, v: u16) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    pub fn i32(&mut self, v: i32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    pub fn f32(&mut self, v: f32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    /// Writes a NUL terminated string.
+    pub fn cstr(&mut self, s: &[u8]) {
+        self.bytes(s);
+        self.u8(0);
+    }
+
+    /// Pads with zeros until the length written since `start` is a multiple of `n`.
+    pub fn align(&mut self, start: usize, n: usize) {
+        let len = self.pos() - start;
+        if !len.is_multiple_of(n) {
+            self.zeros(n - len % n);
+        }
+    }
+}
+
+/// Cursor over the little-endian bytes of a file.
+pub struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn at(bytes: &'b [u8], pos: usize) -> Self {
+        Self { bytes, pos }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.pos)
+    }
+
+    pub fn bytes(&mut self, n: usize) -> Result<&'b [u8], Error> {
+        if self.remaining() < n {
+            return Err(Error::Simple(format!(
+                "unexpected end of file at offset {:#x}",
+                self.pos
+            )));
+        }
+        let b = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(b)
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+        self.bytes(n).map(|_| ())
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn i32(&mut self) -> Result<i32, Error> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn f32(&mut self) -> Result<f32, Error> {
+        Ok(f32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn magic(&mut self, expected: &[u8; 4]) -> Result<(), Error> {
+        let pos = self.pos;
+        let m = self.bytes(4)?;
+        if m != expected {
+            return Err(Error::Simple(format!(
+                "expected magic `{}` at offset {:#x}, found `{}`",
+                String::from_utf8_lossy(expected),
+                pos,
+                String::from_utf8_lossy(m)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reads a NUL terminated string.
+    pub fn cstr(&mut self) -> Result<&'b [u8], Error> {
+        let rest = &self.bytes[self.pos.min(self.bytes.len())..];
+        let len = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(Error::Simple(format!(
+                "unterminated string at offset {:#x}",
+                self.pos
+            )))?;
+        self.pos += len + 1;
+        Ok(&rest[..len])
+    }
+
+    /// Skips the padding until the length read since `start` is a multiple of `n`.
+    pub fn align(&mut self, start: usize, n: usize) {
+        let len = self.pos - start;
+        if !len.is_multiple_of(n) {
+            self.pos += n - len % n;
+        }
+    }
+}
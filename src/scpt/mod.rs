@@ -0,0 +1,164 @@
+//! On-disk layout of the SCPT files, written by `code_gen` and read by the decompiler.
+mod encoding;
+mod io;
+
+pub use encoding::Encoding;
+pub use io::{Reader, Writer};
+
+use crate::error::Error;
+
+pub const MAGIC: &[u8; 4] = b"SCPT";
+pub const SUB_MAGIC: &[u8; 4] = b"ECLH";
+/// Version of the files of th15 to th19, which all share this layout.
+pub const VERSION: u16 = 1;
+/// Position of the include lists, right after the header.
+pub const INCLUDE_OFFSET: u32 = 36;
+pub const ANIM_MAGIC: &[u8; 4] = b"ANIM";
+pub const ECLI_MAGIC: &[u8; 4] = b"ECLI";
+/// Tags of the variadic parameters, before their value.
+pub const VARARG_INT: &[u8; 4] = b"ii\0\0";
+pub const VARARG_FLOAT: &[u8; 4] = b"ff\0\0";
+
+pub struct Header {
+    pub version: u16,
+    pub include_length: u16,
+    pub include_offset: u32,
+    pub sub_count: u32,
+}
+
+impl Header {
+    pub fn write(&self, w: &mut Writer) {
+        w.bytes(MAGIC);
+        w.u16(self.version);
+        w.u16(self.include_length);
+        w.u32(self.include_offset);
+        w.zeros(4);
+        w.u32(self.sub_count);
+        w.zeros(16);
+    }
+
+    pub fn read(r: &mut Reader) -> Result<Self, Error> {
+        r.magic(MAGIC)?;
+        let version = r.u16()?;
+        if version != VERSION {
+            return Err(Error::Simple(format!(
+                "SCPT version {version}, expected {VERSION}"
+            )));
+        }
+        let include_length = r.u16()?;
+        let include_offset = r.u32()?;
+        r.skip(4)?;
+        let sub_count = r.u32()?;
+        r.skip(16)?;
+        Ok(Self {
+            version,
+            include_length,
+            include_offset,
+            sub_count,
+        })
+    }
+}
+
+/// List of anm or ecl files, aligned on 4 bytes.
+pub fn write_inc_list(w: &mut Writer, magic: &[u8; 4], names: &[&[u8]]) {
+    let start = w.pos();
+    w.bytes(magic);
+    w.u32(names.len() as u32);
+    for n in names {
+        w.cstr(n);
+    }
+    w.align(start, 4);
+}
+
+pub fn read_inc_list<'b>(r: &mut Reader<'b>, magic: &[u8; 4]) -> Result<Vec<&'b [u8]>, Error> {
+    let start = r.pos();
+    r.magic(magic)?;
+    let count = r.u32()?;
+    let mut l = Vec::new();
+    for _ in 0..count {
+        l.push(r.cstr()?);
+    }
+    r.align(start, 4);
+    Ok(l)
+}
+
+pub struct SubHeader {
+    /// Position of the instructions, from the start of the header.
+    pub data_offset: u32,
+}
+
+impl SubHeader {
+    pub const SIZE: u32 = 16;
+
+    pub fn write(&self, w: &mut Writer) {
+        w.bytes(SUB_MAGIC);
+        w.u32(self.data_offset);
+        w.zeros(8);
+    }
+
+    pub fn read(r: &mut Reader) -> Result<Self, Error> {
+        r.magic(SUB_MAGIC)?;
+        let data_offset = r.u32()?;
+        r.skip(8)?;
+        Ok(Self { data_offset })
+    }
+}
+
+pub struct InstrHeader {
+    pub time: u32,
+    pub opcode: u16,
+    /// Size of the instruction, header included.
+    pub size: u16,
+    pub param_mask: u16,
+    pub rank_mask: u8,
+    pub param_count: u8,
+    /// Number of parameters read from the stack.
+    pub stack_refs: u32,
+}
+
+impl InstrHeader {
+    pub const SIZE: u16 = 16;
+
+    pub fn write(&self, w: &mut Writer) {
+        w.u32(self.time);
+        w.u16(self.opcode);
+        w.u16(self.size);
+        w.u16(self.param_mask);
+        w.u8(self.rank_mask);
+        w.u8(self.param_count);
+        w.u32(self.stack_refs);
+    }
+
+    pub fn read(r: &mut Reader) -> Result<Self, Error> {
+        Ok(Self {
+            time: r.u32()?,
+            opcode: r.u16()?,
+            size: r.u16()?,
+            param_mask: r.u16()?,
+            rank_mask: r.u8()?,
+            param_count: r.u8()?,
+            stack_refs: r.u32()?,
+        })
+    }
+}
+
+/// Size of a string argument: its length, then the string, NUL terminated
+/// and padded to 4 bytes.
+pub fn str_arg_size(len: usize) -> usize {
+    4 + (len + 1).next_multiple_of(4)
+}
+
+pub fn write_str_arg(w: &mut Writer, s: &[u8]) {
+    let padded = str_arg_size(s.len()) - 4;
+    w.u32(padded as u32);
+    w.bytes(s);
+    w.zeros(padded - s.len());
+}
+
+/// The string of a string argument, without its padding.
+pub fn read_str_arg<'b>(r: &mut Reader<'b>) -> Result<&'b [u8], Error> {
+    let len = r.u32()? as usize;
+    let b = r.bytes(len)?;
+    let end = b.iter().position(|c| *c == 0).unwrap_or(b.len());
+    Ok(&b[..end])
+}
@@ -0,0 +1,55 @@
+//! The names close to an unknown one, for the "did you mean" notes.
+
+use crate::error::{Diagnostic, Severity};
+
+/// Number of character insertions, deletions, substitutions and swaps of
+/// two neighbours from `a` to `b`, the case being ignored.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut before: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 0..a.len() {
+        let mut cur = vec![i + 1];
+        for j in 0..b.len() {
+            let mut d = (prev[j] + usize::from(a[i] != b[j]))
+                .min(prev[j + 1] + 1)
+                .min(cur[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                d = d.min(before[j - 1] + 1);
+            }
+            cur.push(d);
+        }
+        before = std::mem::replace(&mut prev, cur);
+    }
+    prev[b.len()]
+}
+
+/// The candidates close enough to `name` to be what was meant, the closest
+/// first, at most 3 of them.
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let max = (name.chars().count() / 3).max(1);
+    let mut found: Vec<_> = candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| (distance(name, c), c))
+        .filter(|(d, _)| *d <= max)
+        .collect();
+    found.sort();
+    found.dedup();
+    found.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
+/// Adds `names` to `d`, as a note and as its suggestions.
+pub fn did_you_mean(mut d: Diagnostic, names: &[String]) -> Diagnostic {
+    let note = match names {
+        [] => return d,
+        [n] => format!("did you mean `{n}`?"),
+        _ => {
+            let list: Vec<_> = names.iter().map(|n| format!("`{n}`")).collect();
+            format!("did you mean one of {}?", list.join(", "))
+        }
+    };
+    d.suggestions.extend(names.iter().cloned());
+    d.with_note(Diagnostic::simple(Severity::Note, &note))
+}
@@ -0,0 +1,99 @@
+use eclc::{Compiler, SourceFile, Token};
+
+const SOURCE: &str = "\
+// stage 1
+
+/// The first wave.
+/// Called by the game.
+sub main() {
+    wait(60); // a second
+    /* two */ wait(120);
+}
+";
+
+fn tokens<'c>(compiler: &'c Compiler, src: &str) -> Vec<Token<&'c str>> {
+    let src = SourceFile::from_string("lexer.ecs", src.to_owned());
+    compiler.tokens(&src).with_trivia().collect()
+}
+
+fn texts(trivia: &[eclc::Trivia]) -> Vec<&str> {
+    trivia.iter().map(|t| t.text.as_str()).collect()
+}
+
+/// The comments above a sub, doc comments included, lead its keyword.
+#[test]
+fn leading_trivia() {
+    let compiler = Compiler::new(Default::default());
+    let tokens = tokens(&compiler, SOURCE);
+    let sub = tokens.iter().find(|t| t.kind == "kw_sub").unwrap();
+    assert_eq!(
+        texts(&sub.leading),
+        [
+            "// stage 1\n",
+            "\n",
+            "/// The first wave.\n",
+            "/// Called by the game.\n"
+        ]
+    );
+    let docs: Vec<_> = sub.leading.iter().filter(|t| t.is_comment()).collect();
+    assert_eq!(docs.len(), 3);
+    assert_eq!(docs[1].loc.line, 2);
+    assert_eq!(docs[1].loc.span, 0..20);
+
+    // a comment before a token on its line leads it
+    let second_wait = tokens.iter().filter(|t| t.text == "wait").nth(1).unwrap();
+    assert_eq!(texts(&second_wait.leading), ["    ", "/* two */", " "]);
+}
+
+/// A token is trailed by what follows it on its line, newline included.
+#[test]
+fn trailing_trivia() {
+    let compiler = Compiler::new(Default::default());
+    let tokens = tokens(&compiler, SOURCE);
+    let first_semicolon = tokens.iter().find(|t| t.kind == ";").unwrap();
+    assert_eq!(texts(&first_semicolon.trailing), [" ", "// a second\n"]);
+    let lb = tokens.iter().find(|t| t.kind == "lb").unwrap();
+    assert_eq!(texts(&lb.trailing), ["\n"]);
+    let eof = tokens.last().unwrap();
+    assert!(eof.text.is_empty() && eof.leading.is_empty());
+}
+
+/// The tokens and their trivia give the source back.
+#[test]
+fn lossless() {
+    let compiler = Compiler::new(Default::default());
+    let tokens = tokens(&compiler, SOURCE);
+    let mut text = String::new();
+    for t in &tokens {
+        text.extend(t.leading.iter().map(|l| l.text.as_str()));
+        text.push_str(&t.text);
+        text.extend(t.trailing.iter().map(|l| l.text.as_str()));
+    }
+    assert_eq!(text, SOURCE);
+}
+
+/// Without the option, the trivia is dropped as before.
+#[test]
+fn without_trivia() {
+    let compiler = Compiler::new(Default::default());
+    let src = SourceFile::from_string("lexer.ecs", SOURCE.to_owned());
+    assert!(compiler
+        .tokens(&src)
+        .all(|t| t.leading.is_empty() && t.trailing.is_empty()));
+}
+
+/// A character the grammar doesn't know is one error token, however many
+/// bytes it takes, and the lexer goes on after it.
+#[test]
+fn unknown_characters() {
+    let compiler = Compiler::new(Default::default());
+    let src = "wait(1);「é\nwait(2);";
+    let tokens = tokens(&compiler, src);
+    let errors: Vec<_> = tokens
+        .iter()
+        .filter(|t| t.kind == "ERROR")
+        .map(|t| (t.text.as_str(), t.loc.span.clone()))
+        .collect();
+    assert_eq!(errors, [("「", 8..11), ("é", 11..13)]);
+    assert_eq!(tokens.iter().filter(|t| t.text == "wait").count(), 2);
+}
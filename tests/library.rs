@@ -0,0 +1,174 @@
+use eclc::{ecl_instructions::EclMap, Compiler, Level, Lint, Session, Severity, SourceFile};
+
+fn compiler() -> Compiler {
+    Compiler::new(Default::default())
+}
+
+#[test]
+fn compile_str() {
+    let compiler = compiler();
+    let mut session = Session::new(&compiler);
+    let bytes = session
+        .compile_str("main.ecs", "sub main() { wait(60); }")
+        .unwrap();
+    assert_eq!(&bytes[..4], b"SCPT");
+    assert!(session.diagnostics().is_empty());
+}
+
+/// Hexadecimal, binary and octal literals have their value.
+#[test]
+fn int_literals() {
+    let compiler = compiler();
+    let compile = |src| {
+        Session::new(&compiler)
+            .compile_str("main.ecs", src)
+            .unwrap()
+    };
+    assert_eq!(
+        compile("sub main() { wait(0x10); wait(0b10); wait(010); wait(0); }"),
+        compile("sub main() { wait(16); wait(2); wait(8); wait(0); }")
+    );
+    let mut session = Session::new(&compiler);
+    let diagnostics = session
+        .compile_str("main.ecs", "sub main() { wait(0x1FFFFFFFF); }")
+        .unwrap_err();
+    assert_eq!(
+        diagnostics[0].message,
+        "integer literal `0x1FFFFFFFF` is out of range"
+    );
+}
+
+/// The errors come back as data, and render with their file.
+#[test]
+fn compile_str_errors() {
+    let compiler = compiler();
+    let mut session = Session::new(&compiler);
+    let diagnostics = session
+        .compile_str("bad.ecs", "sub main() {\n    wait(Z);\n}")
+        .unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(d.severity, Severity::Error);
+    assert_eq!(d.message, "unresolved identifier `Z`");
+    assert!(session.render(d).contains("bad.ecs:2:10"));
+}
+
+/// A compiler is shared by the sessions of several threads.
+#[test]
+fn threads() {
+    let compiler = compiler();
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let compiler = &compiler;
+                s.spawn(move || {
+                    let src = format!("sub main() {{ wait({i}); }}");
+                    Session::new(compiler).compile_str("main.ecs", &src)
+                })
+            })
+            .collect();
+        let bins: Vec<_> = handles
+            .into_iter()
+            .map(|h| h.join().unwrap().unwrap())
+            .collect();
+        assert!(bins
+            .windows(2)
+            .all(|w| w[0] != w[1] && w[0].len() == w[1].len()));
+    });
+}
+
+/// The diagnostics of a session stay in it, whatever ran before on the
+/// thread.
+#[test]
+fn session_diagnostics() {
+    let compiler = compiler();
+    let mut bad = Session::new(&compiler);
+    let mut good = Session::new(&compiler);
+    let _ = bad.compile_str("bad.ecs", "sub main() { wait(Z); }");
+    let _ = bad.parse(SourceFile::from_string("bad.ecs", "sub {".to_owned()));
+    assert!(good
+        .compile_str("good.ecs", "sub main() { wait(1); }")
+        .is_ok());
+    assert!(good.diagnostics().is_empty());
+    assert!(!bad.diagnostics().is_empty());
+}
+
+/// A comment inside a statement is moved after it, with a warning.
+#[test]
+fn format_moved_comments() {
+    let compiler = compiler();
+    let mut session = Session::new(&compiler);
+    let src = "sub main() {\n    int x = 1 /* inline */ + 1; // end\n    wait(x);\n}\n";
+    let formatted = session
+        .format(SourceFile::from_string("fmt.ecs", src.to_owned()))
+        .unwrap();
+    assert_eq!(
+        formatted,
+        "sub main() {\n    int x = 1 + 1; /* inline */ // end\n    wait(x);\n}\n"
+    );
+    let diagnostics = session.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].lint, Some(Lint::MovedComments));
+    assert_eq!(diagnostics[0].loc.as_ref().unwrap().span, 14..26);
+
+    let mut session = Session::new(&compiler);
+    session.lints.set(Lint::MovedComments, Level::Allow);
+    assert!(session
+        .format(SourceFile::from_string("fmt.ecs", src.to_owned()))
+        .is_ok());
+    assert!(session.diagnostics().is_empty());
+}
+
+/// Each compiler has its own instructions: a map loaded in one of them
+/// changes nothing for the others.
+#[test]
+fn instruction_maps() {
+    let map = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/map/custom.eclmap");
+    let mut mapped = compiler();
+    mapped
+        .instructions
+        .load_map(&EclMap::open(map).unwrap())
+        .unwrap();
+    let built_in = compiler();
+    let src = "sub main() { sleep(10); }";
+    assert!(Session::new(&mapped).compile_str("main.ecs", src).is_ok());
+    assert!(Session::new(&built_in)
+        .compile_str("main.ecs", src)
+        .is_err());
+}
+
+/// The grammar given at runtime parses the same as the built-in one.
+#[test]
+fn grammar_override() {
+    let grammar = concat!(env!("CARGO_MANIFEST_DIR"), "/test.grammar");
+    let runtime = Compiler::from_grammar(grammar, Default::default()).unwrap();
+    let src = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/test.code")).unwrap();
+    let built_in = compiler();
+    let a = Session::new(&built_in)
+        .compile_str("test.code", &src)
+        .unwrap();
+    let b = Session::new(&runtime)
+        .compile_str("test.code", &src)
+        .unwrap();
+    assert_eq!(a, b);
+}
+
+/// A damaged file is an error for the decompiler, not a crash.
+#[test]
+fn decompile_damaged() {
+    let compiler = compiler();
+    let bytes = Session::new(&compiler)
+        .compile_str("main.ecs", "sub main() { wait(60); }")
+        .unwrap();
+    let decompile =
+        |b: &[u8]| eclc::decompiler::decompile(b, &compiler.instructions, Default::default());
+    assert!(decompile(&bytes).is_ok());
+    for len in 0..bytes.len() {
+        let _ = decompile(&bytes[..len]);
+    }
+    // the instructions of the sub past its end
+    let sub = bytes.windows(4).position(|w| w == b"ECLH").unwrap();
+    let mut damaged = bytes.clone();
+    damaged[sub + 4..sub + 8].copy_from_slice(&0x1000u32.to_le_bytes());
+    assert!(decompile(&damaged).is_err());
+}
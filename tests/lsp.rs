@@ -0,0 +1,181 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+const URI: &str = "file:///project/stage.ecs";
+
+const SOURCE: &str = "\
+const int SPEED = 3;
+
+sub main() {
+    int count = 2;
+    wait(SPEED + count);
+    @helper(1);
+    wiat(2);
+    goto end @ 0;
+end:
+    count = 1;
+}
+
+/// Waits for `a` frames.
+sub helper(int a) {
+    wait(a);
+}
+";
+
+/// An editor scripted by the test, talking to `eclc lsp`.
+struct Client {
+    server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_eclc"))
+            .arg("lsp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("could not run eclc lsp");
+        let stdin = server.stdin.take().unwrap();
+        let stdout = BufReader::new(server.stdout.take().unwrap());
+        Self {
+            server,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    fn send(&mut self, msg: Value) {
+        let body = msg.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut len = 0;
+        loop {
+            let mut line = String::new();
+            self.stdout.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(n) = line.strip_prefix("Content-Length: ") {
+                len = n.parse().unwrap();
+            }
+        }
+        let mut body = vec![0; len];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Result of the request, the notifications sent before being skipped.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let msg = self.receive();
+            if msg["id"] == id {
+                assert!(msg.get("error").is_none(), "{msg}");
+                return msg["result"].clone();
+            }
+        }
+    }
+
+    /// Result of a request at `line` and `character` in the document.
+    fn at(&mut self, method: &str, line: u32, character: u32) -> Value {
+        let params = json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        });
+        self.request(method, params)
+    }
+}
+
+#[test]
+fn language_server() {
+    let mut client = Client::start();
+    let init = client.request("initialize", json!({ "capabilities": {} }));
+    assert_eq!(init["capabilities"]["hoverProvider"], true);
+    client.notify("initialized", json!({}));
+
+    let document = json!({ "uri": URI, "languageId": "ecl", "version": 1, "text": SOURCE });
+    client.notify("textDocument/didOpen", json!({ "textDocument": document }));
+    let published = client.receive();
+    assert_eq!(published["method"], "textDocument/publishDiagnostics");
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1, "{published}");
+    assert_eq!(diagnostics[0]["code"], "E0007");
+    assert_eq!(
+        diagnostics[0]["range"]["start"],
+        json!({ "line": 6, "character": 4 })
+    );
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("did you mean `wait`?"));
+
+    // the instruction, the sub, a variable and a label
+    let hover = client.at("textDocument/hover", 4, 5);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("wait(int)"), "{text}");
+    let hover = client.at("textDocument/hover", 5, 6);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("sub helper(int a)"), "{text}");
+    assert!(text.ends_with("```\n\nWaits for `a` frames."), "{text}");
+    let def = client.at("textDocument/definition", 5, 7);
+    assert_eq!(def["range"]["start"], json!({ "line": 13, "character": 4 }));
+    let def = client.at("textDocument/definition", 4, 18);
+    assert_eq!(def["range"]["start"], json!({ "line": 3, "character": 8 }));
+    let def = client.at("textDocument/definition", 7, 10);
+    assert_eq!(def["range"]["start"], json!({ "line": 8, "character": 0 }));
+
+    let items = client.at("textDocument/completion", 4, 5);
+    let labels: Vec<_> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["label"].as_str().unwrap())
+        .collect();
+    for label in ["wait", "PI", "AIM_ST", "SPEED"] {
+        assert!(labels.contains(&label), "{label}");
+    }
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let names: Vec<_> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["main", "helper"]);
+
+    // fixing the typo clears the diagnostics
+    let fixed = SOURCE.replace("wiat", "wait");
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": fixed }],
+        }),
+    );
+    let published = client.receive();
+    assert_eq!(published["params"]["diagnostics"], json!([]));
+
+    client.request("shutdown", Value::Null);
+    client.notify("exit", Value::Null);
+    assert!(client.server.wait().unwrap().success());
+}
@@ -0,0 +1,543 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn eclc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_eclc"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .expect("could not run eclc")
+}
+
+/// Runs eclc with `input` on its standard input.
+fn eclc_stdin(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_eclc"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("could not run eclc");
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().expect("could not run eclc")
+}
+
+fn assert_success(out: &Output, what: &str) {
+    assert!(
+        out.status.success(),
+        "{what} failed:\n{}{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+/// Compile, decompile and compile again every source of tests/verify.
+#[test]
+fn round_trip() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/verify");
+    let mut sources: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "ecs"))
+        .collect();
+    sources.sort();
+    assert!(!sources.is_empty());
+    for src in sources {
+        let src = src.to_str().unwrap();
+        assert_success(&eclc(&["verify", "-q", src]), src);
+    }
+}
+
+#[test]
+fn round_trip_example() {
+    assert_success(&eclc(&["verify", "-q", "test.code"]), "test.code");
+}
+
+/// The binary generated for the example is checked in as out.ecl.
+#[test]
+fn example_binary() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = std::env::temp_dir().join(format!("eclc-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bin = dir.join("test.ecl");
+    assert_success(
+        &eclc(&["-q", "test.code", "-o", bin.to_str().unwrap()]),
+        "test.code",
+    );
+    let same = std::fs::read(&bin).unwrap() == std::fs::read(root.join("out.ecl")).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    assert!(same, "test.code doesn't compile to out.ecl anymore");
+}
+
+/// Instructions renamed and added by an eclmap are used both ways.
+#[test]
+fn round_trip_with_map() {
+    let map = "tests/map/custom.eclmap";
+    let src = "tests/map/custom.ecs";
+    assert_success(&eclc(&["verify", "-q", "--map", map, src]), src);
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled without its map");
+}
+
+/// `☃` has no Shift-JIS encoding.
+#[test]
+fn encodings() {
+    let src = "tests/encoding/snowman.ecs";
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled to Shift-JIS");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("can't be encoded in Shift-JIS"));
+    assert_success(&eclc(&["verify", "-q", "--encoding", "utf8", src]), src);
+}
+
+#[test]
+fn bad_escape() {
+    let src = "tests/errors/bad_escape.ecs";
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown escape sequence `\\q`"));
+}
+
+/// An int literal too large is an error on the literal.
+#[test]
+fn int_literals() {
+    let src = "tests/errors/int_literals.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("integer literal `3000000000` is out of range"));
+    assert!(stderr.contains(&format!("{src}:3:10")), "{stderr}");
+}
+
+/// A `-N:` label is a backwards time label, and an error if it goes
+/// before time 0.
+#[test]
+fn negative_time() {
+    let src = "tests/errors/negative_time.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("time label `-30:` goes back from time 60"));
+    assert!(stderr.contains("time label `-40:` goes before time 0"));
+    assert!(stderr.contains(&format!("{src}:6:6")), "{stderr}");
+    assert!(stderr.contains("due to 1 previous error"), "{stderr}");
+}
+
+#[test]
+fn not_constant() {
+    let src = "tests/errors/not_constant.ecs";
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("value of `M` is not a constant"));
+    assert!(stderr.contains(&format!("{src}:5:15")), "{stderr}");
+}
+
+/// A constant which overflows or divides by zero is an error on its
+/// operation, not a crash.
+#[test]
+fn constant_overflow() {
+    let src = "tests/errors/overflow.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("value of `BIG` can't be computed: int overflow"));
+    assert!(stderr.contains("value of `DIV` can't be computed: division by zero"));
+    assert!(stderr.contains("value of `REM` can't be computed: division by zero"));
+    assert!(stderr.contains(&format!("{src}:4:20")), "{stderr}");
+    // the variant after the last int
+    assert!(stderr.contains("value of `Last::AFTER` can't be computed: int overflow"));
+    assert!(stderr.contains(&format!("{src}:5:31")), "{stderr}");
+    assert!(stderr.contains("due to 5 previous errors"), "{stderr}");
+}
+
+/// Enum variants, qualified or bare, compile to their values.
+#[test]
+fn enums() {
+    let hex = |src: &str| {
+        let out = eclc(&["build", "--emit", "hex", src]);
+        assert_success(&out, src);
+        out.stdout
+    };
+    assert_eq!(hex("tests/enum/enums.ecs"), hex("tests/enum/literals.ecs"));
+}
+
+/// Includes are relative to the including file, and only included once.
+#[test]
+fn includes() {
+    let src = "tests/include/main.ecs";
+    assert_success(&eclc(&["verify", "-q", src]), src);
+    let src = "tests/include/cycle.ecs";
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("tests/include/lib/loop.ecs:1:9"),
+        "{stderr}"
+    );
+    // `fan` is in both included files
+    let src = "tests/include/duplicate.ecs";
+    let out = eclc(&["verify", "-q", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("sub `fan` is already defined"), "{stderr}");
+    assert!(stderr.contains("tests/include/lib/fan.ecs:1:5"), "{stderr}");
+    assert!(
+        stderr.contains("tests/include/lib/common.ecs:6:5"),
+        "{stderr}"
+    );
+}
+
+/// Several inputs are processed at the same time, their messages printed
+/// in the order of the inputs.
+#[test]
+fn jobs() {
+    let srcs = [
+        "tests/verify/calls.ecs",
+        "tests/errors/not_constant.ecs",
+        "tests/verify/strings.ecs",
+        "tests/verify/nesting.ecs",
+    ];
+    let mut args = vec!["verify", "-j", "3"];
+    args.extend(srcs);
+    let out = eclc(&args);
+    assert!(!out.status.success(), "not_constant.ecs compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let pos = |s: &str| stderr.find(s).unwrap_or_else(|| panic!("{s}:\n{stderr}"));
+    let calls = pos("`tests/verify/calls.ecs`");
+    let error = pos("could not verify `tests/errors/not_constant.ecs`");
+    let strings = pos("`tests/verify/strings.ecs`");
+    let nesting = pos("`tests/verify/nesting.ecs`");
+    assert!(
+        calls < error && error < strings && strings < nesting,
+        "{stderr}"
+    );
+
+    let out = eclc(&["verify", "-j", "0", "test.code"]);
+    assert!(!out.status.success());
+}
+
+/// `-` reads the standard input, the output going to stdout.
+#[test]
+fn stdin_input() {
+    let src = std::fs::read("tests/verify/calls.ecs").unwrap();
+    let out = eclc_stdin(&["--emit", "hex", "-q", "-"], &src);
+    assert_success(&out, "build -");
+    let file = eclc(&["--emit", "hex", "-q", "tests/verify/calls.ecs"]);
+    assert_eq!(out.stdout, file.stdout);
+
+    let out = eclc_stdin(&["fmt", "-q", "-"], b"sub main(){wait(1);}");
+    assert_success(&out, "fmt -");
+    assert_eq!(out.stdout, b"sub main() {\n    wait(1);\n}\n");
+
+    let out = eclc_stdin(&["-"], b"sub main() { wait(x); }");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("<stdin>:1:19"), "{stderr}");
+}
+
+/// Compilation goes on after an error, to report all of them with their count.
+#[test]
+fn multiple_errors() {
+    let src = "tests/errors/multiple.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    for pos in [
+        "1:15", "5:5", "7:5", "12:5", "13:9", "17:10", "21:9", "22:5", "26:1",
+    ] {
+        assert!(stderr.contains(&format!("{src}:{pos}")), "{pos}:\n{stderr}");
+    }
+    assert!(stderr.contains("due to 9 previous errors"), "{stderr}");
+}
+
+/// The parser goes on after a syntax error at the next statement or sub,
+/// and the rest of the file is still checked.
+#[test]
+fn syntax_errors() {
+    let src = "tests/errors/syntax.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    for pos in ["2:12", "4:13", "5:15", "13:1", "17:5", "3:5", "16:10"] {
+        assert!(stderr.contains(&format!("{src}:{pos}")), "{pos}:\n{stderr}");
+    }
+    assert!(stderr.contains("due to 7 previous errors"), "{stderr}");
+}
+
+/// Number of warnings in the output of eclc, whose colors are left out.
+fn warning_count(stderr: &str) -> usize {
+    stderr
+        .lines()
+        .filter(|l| {
+            let mut text = String::new();
+            let mut in_escape = false;
+            for c in l.chars() {
+                match c {
+                    '\x1b' => in_escape = true,
+                    'm' if in_escape => in_escape = false,
+                    c if !in_escape => text.push(c),
+                    _ => {}
+                }
+            }
+            text.starts_with("warning:")
+        })
+        .count()
+}
+
+/// Each lint warns once in tests/warnings/lints.ecs, which still compiles.
+#[test]
+fn warnings() {
+    let src = "tests/warnings/lints.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert_success(&out, src);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    for pos in [
+        "20:5", "17:5", "11:13", "5:9", "7:9", "25:12", "26:14", "30:5",
+    ] {
+        assert!(stderr.contains(&format!("{src}:{pos}")), "{pos}:\n{stderr}");
+    }
+    assert!(
+        stderr.contains("`010` is an octal literal, worth 8"),
+        "{stderr}"
+    );
+    assert_eq!(warning_count(&stderr), 8, "{stderr}");
+}
+
+/// `-A`, `-W` and `-D` set the level of a lint or of all of them, the last
+/// one given winning, and `-Werror` makes the warnings fail the build.
+#[test]
+fn lint_levels() {
+    let src = "tests/warnings/lints.ecs";
+    let out = eclc(&[
+        "build",
+        "--emit",
+        "hex",
+        "-A",
+        "warnings",
+        "-W",
+        "unused_subs",
+        src,
+    ]);
+    assert_success(&out, src);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(warning_count(&stderr), 1, "{stderr}");
+    assert!(
+        stderr.contains("sub `forgotten` is never called"),
+        "{stderr}"
+    );
+
+    let out = eclc(&["build", "--emit", "hex", "-D", "unused_variables", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(warning_count(&stderr), 7, "{stderr}");
+    assert!(stderr.contains("due to 1 previous error"), "{stderr}");
+
+    let out = eclc(&["build", "--emit", "hex", "-Werror", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("due to 8 previous errors"), "{stderr}");
+
+    let out = eclc(&["build", "-A", "unused_things", src]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+/// `--message-format=json` prints one object per diagnostic, the near
+/// matches of an unknown instruction as suggestions.
+#[test]
+fn json_messages() {
+    let src = "tests/errors/near_match.ecs";
+    let out = eclc(&["build", "--message-format=json", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let messages: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("not a JSON line"))
+        .collect();
+    assert_eq!(messages.len(), 2, "{stderr}");
+    let d = &messages[0];
+    assert_eq!(d["severity"], "error");
+    assert_eq!(d["file"], src);
+    assert_eq!(d["span"]["line_start"], 2);
+    assert_eq!(d["span"]["column_start"], 5);
+    assert_eq!(d["span"]["column_end"], 9);
+    let suggestions = d["suggestions"].as_array().unwrap();
+    assert!(suggestions.iter().any(|s| s == "ins_23/wait(int)"), "{d}");
+    assert_eq!(messages[1]["span"], serde_json::Value::Null);
+
+    let src = "tests/warnings/lints.ecs";
+    let out = eclc(&["build", "--message-format=json", "--emit", "hex", src]);
+    assert_success(&out, src);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let codes: Vec<_> = stderr
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .map(|d| d["code"].clone())
+        .collect();
+    assert!(codes.contains(&"unused_subs".into()), "{stderr}");
+    // the diagnostics stay out of the output
+    let quiet = eclc(&["build", "-A", "warnings", "--emit", "hex", src]);
+    assert_eq!(out.stdout, quiet.stdout);
+}
+
+/// The type errors are at the operands, in JSON too.
+#[test]
+fn type_errors() {
+    let src = "tests/errors/types.ecs";
+    let out = eclc(&["build", "--message-format=json", "--emit", "hex", src]);
+    assert!(!out.status.success(), "{src} compiled");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let spans: Vec<_> = stderr
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter(|d| d["code"] == "E0016")
+        .map(|d| {
+            assert_eq!(d["file"], src);
+            let span = &d["span"];
+            (span["line_start"].clone(), span["column_start"].clone())
+        })
+        .collect();
+    assert_eq!(
+        spans,
+        [(3.into(), 10.into()), (4.into(), 14.into())],
+        "{stderr}"
+    );
+}
+
+/// The errors carry their code, which `--explain` explains.
+#[test]
+fn error_codes() {
+    let src = "tests/errors/multiple.ecs";
+    let out = eclc(&["build", "--message-format=json", "--emit", "hex", src]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let codes: Vec<_> = stderr
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter_map(|d| d["code"].as_str().map(str::to_owned))
+        .collect();
+    for code in ["E0007", "E0008", "E0009", "E0010", "E0013", "E0014"] {
+        assert!(codes.iter().any(|c| c == code), "{code}:\n{stderr}");
+    }
+
+    let out = eclc(&["build", "--emit", "hex", src]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("eclc --explain E0007"), "{stderr}");
+
+    let out = eclc(&["--explain", "E0008"]);
+    assert_success(&out, "--explain");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("int a = 2;"), "{stdout}");
+
+    let out = eclc(&["--explain", "19"]);
+    assert_success(&out, "--explain");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("bug of eclc"), "{stdout}");
+
+    let out = eclc(&["--explain", "E9999"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+/// The names close to an unknown one are suggested.
+#[test]
+fn suggestions() {
+    let src = "tests/errors/typos.ecs";
+    let out = eclc(&["build", "--emit", "hex", src]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    for note in ["`wait`", "`@helper`", "`count`", "`SPEED`", "`etSprite`"] {
+        assert!(
+            stderr.contains(&format!("did you mean {note}?")),
+            "{note}:\n{stderr}"
+        );
+    }
+    assert!(
+        stderr.contains("instruction `etSprte(int, int)` does not exist"),
+        "{stderr}"
+    );
+
+    let out = eclc(&["build", "--message-format=json", "--emit", "hex", src]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let suggestions: Vec<_> = stderr
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter_map(|d| d["suggestions"].as_array().cloned())
+        .flatten()
+        .collect();
+    assert!(suggestions.iter().any(|s| s == "wait"), "{stderr}");
+}
+
+/// tests/fmt/messy.ecs is formatted as tests/fmt/formatted.ecs, which
+/// `--check` accepts.
+#[test]
+fn fmt() {
+    let dir = std::env::temp_dir().join(format!("eclc-fmt-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let out_path = dir.join("messy.ecs");
+    let out = out_path.to_str().unwrap();
+    assert_success(
+        &eclc(&["fmt", "-q", "tests/fmt/messy.ecs", "-o", out]),
+        "fmt",
+    );
+    let expected = std::fs::read_to_string("tests/fmt/formatted.ecs").unwrap();
+    assert_eq!(std::fs::read_to_string(&out_path).unwrap(), expected);
+
+    assert_success(
+        &eclc(&["fmt", "--check", "tests/fmt/formatted.ecs"]),
+        "fmt --check",
+    );
+    let out = eclc(&["fmt", "--check", "tests/fmt/messy.ecs"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("`tests/fmt/messy.ecs` is not formatted (first difference on line 2)"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// A comment inside the `ecli` list goes after the list, not into the
+/// next enum.
+#[test]
+fn fmt_list_comments() {
+    let dir = std::env::temp_dir().join(format!("eclc-fmt-list-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let out_path = dir.join("list_comments.ecs");
+    let out = out_path.to_str().unwrap();
+    let res = eclc(&["fmt", "tests/fmt/list_comments.ecs", "-o", out]);
+    assert_success(&res, "fmt");
+    let stderr = String::from_utf8_lossy(&res.stderr);
+    assert!(stderr.contains("moved after the statement"), "{stderr}");
+    let expected = std::fs::read_to_string("tests/fmt/list_comments.formatted.ecs").unwrap();
+    assert_eq!(std::fs::read_to_string(&out_path).unwrap(), expected);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Formatting a source doesn't change its binary, and formatting it again
+/// changes nothing.
+#[test]
+fn fmt_keeps_binaries() {
+    let dir = std::env::temp_dir().join(format!("eclc-fmt-bin-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let verify = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/verify");
+    let mut sources: Vec<_> = std::fs::read_dir(verify)
+        .unwrap()
+        .map(|e| e.unwrap().path().to_str().unwrap().to_owned())
+        .filter(|p| p.ends_with(".ecs"))
+        .collect();
+    sources.sort();
+    sources.push("test.code".to_owned());
+    let hex = |src: &str| {
+        let out = eclc(&["-q", "-A", "warnings", "--emit", "hex", src]);
+        assert_success(&out, src);
+        out.stdout
+    };
+    for src in &sources {
+        let formatted = dir.join(Path::new(src).file_name().unwrap());
+        let formatted = formatted.to_str().unwrap();
+        assert_success(&eclc(&["fmt", "-q", src, "-o", formatted]), src);
+        assert_eq!(hex(src), hex(formatted), "{src}");
+        assert_success(&eclc(&["fmt", "--check", formatted]), formatted);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}